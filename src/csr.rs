@@ -0,0 +1,356 @@
+//! Compressed sparse row graph representation.
+
+use crate::edge::EdgeType;
+use crate::graph::{Directed, Graph};
+use crate::node::NodeTrait;
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+use std::iter::Zip;
+use std::marker::PhantomData;
+use std::slice;
+
+/// `Csr<N, E, Ty>` is an immutable graph in compressed sparse row format.
+///
+/// The edges of all nodes are stored in contiguous arrays: row `i`, the edges starting from
+/// the node at index `i`, is `targets[offsets[i]..offsets[i + 1]]` with the matching
+/// `weights`. Targets are node indices sorted in increasing order, so `contains_edge` is a
+/// binary search. An undirected edge is stored in the rows of both of its nodes.
+///
+/// Node indices follow the node insertion order of the `Graph` it was created from, see
+/// `Graph::to_csr`.
+///
+/// # Examples
+///
+/// ```
+/// use safe_graph::Graph;
+///
+/// let graph = Graph::<_, _>::from_edges(&[(1, 3, 0.5), (1, 2, 1.5), (2, 3, 2.5)]);
+/// let csr = graph.to_csr();
+///
+/// assert_eq!(csr.node_index(2), Some(2));
+/// // Neighbors are in node index order.
+/// assert_eq!(csr.neighbors(1).collect::<Vec<_>>(), vec![3, 2]);
+/// assert_eq!(csr.edge_weight(2, 3), Some(&2.5));
+/// assert!(!csr.contains_edge(3, 1));
+/// ```
+#[derive(Clone, Debug)]
+pub struct Csr<N, E, Ty = Directed> {
+    nodes: Vec<N>,
+    index: HashMap<N, usize>,
+    offsets: Vec<usize>,
+    targets: Vec<usize>,
+    weights: Vec<E>,
+    edge_count: usize,
+    ty: PhantomData<Ty>,
+}
+
+impl<N, E, Ty> Csr<N, E, Ty>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+{
+    /// Create a new `Csr` from a graph, keeping its node order.
+    pub fn from_graph<S>(graph: &Graph<N, E, Ty, S>) -> Self
+    where
+        E: Clone,
+        S: BuildHasher,
+    {
+        let nodes: Vec<N> = graph.nodes().collect();
+        let index: HashMap<N, usize> = nodes.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+
+        let mut offsets = Vec::with_capacity(nodes.len() + 1);
+        let mut targets = Vec::with_capacity(graph.edge_count());
+        let mut weights = Vec::with_capacity(graph.edge_count());
+        let mut row = Vec::new();
+
+        offsets.push(0);
+        for &n in &nodes {
+            row.extend(graph.edges(n).map(|(_, b, weight)| (index[&b], weight)));
+            row.sort_by_key(|&(b, _)| b);
+
+            for (b, weight) in row.drain(..) {
+                targets.push(b);
+                weights.push(weight.clone());
+            }
+            offsets.push(targets.len());
+        }
+
+        Self {
+            nodes,
+            index,
+            offsets,
+            targets,
+            weights,
+            edge_count: graph.edge_count(),
+            ty: PhantomData,
+        }
+    }
+
+    /// Whether the graph has directed edges.
+    pub fn is_directed(&self) -> bool {
+        Ty::is_directed()
+    }
+
+    /// Return the number of nodes in the graph.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Return the number of edges in the graph, an undirected edge being counted once.
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    /// Return the index of node `n`, or `None` if the node does not exist in the graph.
+    pub fn node_index(&self, n: N) -> Option<usize> {
+        self.index.get(&n).cloned()
+    }
+
+    /// Return the node at index `i`, or `None` if the index is out of bounds.
+    pub fn node_at(&self, i: usize) -> Option<N> {
+        self.nodes.get(i).cloned()
+    }
+
+    /// Return `true` if the node is contained in the graph.
+    pub fn contains_node(&self, n: N) -> bool {
+        self.index.contains_key(&n)
+    }
+
+    /// Return the nodes of the graph in index order.
+    pub fn nodes(&self) -> &[N] {
+        &self.nodes
+    }
+
+    /// Return the sorted indices of the targets of the edges starting from `a`.
+    ///
+    /// - `Directed`: Outgoing edges from `a`.
+    /// - `Undirected`: All edges from or to `a`.
+    ///
+    /// Return an empty slice if the node doesn't exist.
+    pub fn neighbor_indices(&self, a: N) -> &[usize] {
+        let (start, end) = self.row(a);
+        &self.targets[start..end]
+    }
+
+    /// Return an iterator of all nodes with an edge starting from `a`, in index order.
+    ///
+    /// - `Directed`: Outgoing edges from `a`.
+    /// - `Undirected`: All edges from or to `a`.
+    ///
+    /// Produces an empty iterator if the node doesn't exist.<br>
+    /// Iterator element type is `N`.
+    pub fn neighbors(&self, a: N) -> CsrNeighbors<'_, N> {
+        CsrNeighbors {
+            nodes: &self.nodes,
+            iter: self.neighbor_indices(a).iter(),
+        }
+    }
+
+    /// Return an iterator of the edges starting from `a` with their weight, in index order of
+    /// the targets.
+    ///
+    /// Produces an empty iterator if the node doesn't exist.<br>
+    /// Iterator element type is `(N, N, &E)`.
+    pub fn edges(&self, a: N) -> CsrEdges<'_, N, E> {
+        let (start, end) = self.row(a);
+
+        CsrEdges {
+            source: a,
+            nodes: &self.nodes,
+            iter: self.targets[start..end]
+                .iter()
+                .zip(self.weights[start..end].iter()),
+        }
+    }
+
+    /// Return `true` if the edge connecting `a` with `b` is contained in the graph.
+    pub fn contains_edge(&self, a: N, b: N) -> bool {
+        self.edge_position(a, b).is_some()
+    }
+
+    /// Return a reference to the edge weight connecting `a` with `b`, or
+    /// `None` if the edge does not exist in the graph.
+    pub fn edge_weight(&self, a: N, b: N) -> Option<&E> {
+        self.edge_position(a, b).map(|i| &self.weights[i])
+    }
+
+    /// Return an iterator over all edges of the graph with their weight, in index order.
+    ///
+    /// An undirected edge is yielded once, from its node of lower index.<br>
+    /// Iterator element type is `(N, N, &E)`.
+    pub fn all_edges(&self) -> CsrAllEdges<'_, N, E, Ty> {
+        CsrAllEdges {
+            csr: self,
+            row: 0,
+            position: 0,
+        }
+    }
+
+    /// Return the bounds of the row of node `a`, empty if the node doesn't exist.
+    fn row(&self, a: N) -> (usize, usize) {
+        match self.index.get(&a) {
+            Some(&i) => (self.offsets[i], self.offsets[i + 1]),
+            None => (0, 0),
+        }
+    }
+
+    /// Return the position of edge `(a, b)` in the edge arrays.
+    fn edge_position(&self, a: N, b: N) -> Option<usize> {
+        let (start, end) = self.row(a);
+        let b = *self.index.get(&b)?;
+
+        self.targets[start..end]
+            .binary_search(&b)
+            .ok()
+            .map(|i| start + i)
+    }
+}
+
+/// Iterator over the neighbors of a node of a `Csr`.
+///
+/// Iterator element type is `N`.
+pub struct CsrNeighbors<'a, N> {
+    nodes: &'a [N],
+    iter: slice::Iter<'a, usize>,
+}
+
+impl<'a, N> Iterator for CsrNeighbors<'a, N>
+where
+    N: Copy,
+{
+    type Item = N;
+
+    fn next(&mut self) -> Option<N> {
+        self.iter.next().map(|&i| self.nodes[i])
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, N> ExactSizeIterator for CsrNeighbors<'a, N> where N: Copy {}
+
+/// Iterator over the edges of a node of a `Csr`.
+///
+/// Iterator element type is `(N, N, &E)`.
+pub struct CsrEdges<'a, N, E> {
+    source: N,
+    nodes: &'a [N],
+    iter: Zip<slice::Iter<'a, usize>, slice::Iter<'a, E>>,
+}
+
+impl<'a, N, E> Iterator for CsrEdges<'a, N, E>
+where
+    N: Copy,
+{
+    type Item = (N, N, &'a E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next()
+            .map(|(&b, weight)| (self.source, self.nodes[b], weight))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Iterator over all edges of a `Csr`.
+///
+/// Iterator element type is `(N, N, &E)`.
+pub struct CsrAllEdges<'a, N, E, Ty> {
+    csr: &'a Csr<N, E, Ty>,
+    row: usize,
+    position: usize,
+}
+
+impl<'a, N, E, Ty> Iterator for CsrAllEdges<'a, N, E, Ty>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+{
+    type Item = (N, N, &'a E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let csr = self.csr;
+
+        while self.position < csr.targets.len() {
+            while self.position >= csr.offsets[self.row + 1] {
+                self.row += 1;
+            }
+
+            let (i, target) = (self.position, csr.targets[self.position]);
+            self.position += 1;
+
+            // The other half of an undirected edge comes from the row of its target.
+            if Ty::is_directed() || self.row <= target {
+                return Some((csr.nodes[self.row], csr.nodes[target], &csr.weights[i]));
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{Graph, Undirected};
+
+    #[test]
+    fn from_graph() {
+        let mut graph =
+            Graph::<u32, f32>::from_edges(&[(1, 4, 1.0), (1, 2, 2.0), (4, 2, 3.0), (2, 2, 4.0)]);
+        graph.add_node(3);
+        let csr = graph.to_csr();
+
+        assert_eq!(csr.node_count(), 4);
+        assert_eq!(csr.edge_count(), 4);
+        assert_eq!(csr.nodes(), &[1, 4, 2, 3]);
+        assert_eq!(csr.node_index(3), Some(3));
+        assert_eq!(csr.node_at(1), Some(4));
+        assert_eq!(csr.node_index(5), None);
+
+        // Test the rows are sorted by target index.
+        assert_eq!(csr.neighbor_indices(1), &[1, 2]);
+        assert_eq!(csr.neighbors(1).collect::<Vec<_>>(), vec![4, 2]);
+        assert_eq!(csr.neighbors(3).len(), 0);
+        assert_eq!(csr.neighbors(5).len(), 0);
+        assert_eq!(csr.edges(4).collect::<Vec<_>>(), vec![(4, 2, &3.0)]);
+    }
+
+    #[test]
+    fn edge_lookup() {
+        let graph = Graph::<u32, f32>::from_edges(&[(1, 2, 1.0), (2, 3, 2.0), (2, 2, 3.0)]);
+        let csr = graph.to_csr();
+
+        assert!(csr.contains_edge(2, 2));
+        assert!(!csr.contains_edge(3, 2));
+        assert!(!csr.contains_edge(1, 7));
+        assert_eq!(csr.edge_weight(2, 3), Some(&2.0));
+        assert_eq!(csr.edge_weight(7, 3), None);
+        assert_eq!(
+            csr.all_edges().collect::<Vec<_>>(),
+            vec![(1, 2, &1.0), (2, 2, &3.0), (2, 3, &2.0)]
+        );
+    }
+
+    #[test]
+    fn undirected() {
+        let mut graph =
+            Graph::<u32, u32, Undirected>::from_edges(&[(3, 1, 1), (1, 2, 2), (2, 2, 3)]);
+        graph.add_node(4);
+        let csr = graph.to_csr();
+
+        assert_eq!(csr.edge_count(), 3);
+        assert_eq!(csr.neighbors(1).collect::<Vec<_>>(), vec![3, 2]);
+        assert_eq!(csr.neighbors(2).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(csr.edge_weight(1, 3), Some(&1));
+        assert_eq!(csr.edge_weight(3, 1), Some(&1));
+        assert_eq!(
+            csr.all_edges().collect::<Vec<_>>(),
+            vec![(3, 1, &1), (1, 2, &2), (2, 2, &3)]
+        );
+    }
+}
@@ -4,9 +4,15 @@ use self::Direction::{Incoming, Outgoing};
 use crate::graph::{Directed, Graph, Undirected};
 use crate::node::NodeTrait;
 use crate::traverse::Neighbors;
-use indexmap::map::Iter as IndexMapIter;
+use indexmap::map::{
+    IntoIter as IndexMapIntoIter, Iter as IndexMapIter, IterMut as IndexMapIterMut,
+};
 use indexmap::IndexMap;
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
 use std::marker::PhantomData;
+use std::slice::Iter as SliceIter;
+use std::vec::IntoIter as VecIntoIter;
 
 /// A graph's edge type determines whether is has directed edges or not.
 pub trait EdgeType {
@@ -27,31 +33,32 @@ impl EdgeType for Undirected {
     }
 }
 
-pub struct Edges<'a, N, E: 'a, Ty>
+pub struct Edges<'a, N, E: 'a, Ty, S = RandomState>
 where
     N: 'a + NodeTrait,
     Ty: EdgeType,
 {
     from: N,
-    edges: &'a IndexMap<(N, N), E>,
+    edges: &'a IndexMap<(N, N), E, S>,
     iter: Neighbors<'a, N, Ty>,
 }
 
-impl<'a, N, E, Ty> Edges<'a, N, E, Ty>
+impl<'a, N, E, Ty, S> Edges<'a, N, E, Ty, S>
 where
     N: 'a + NodeTrait,
     Ty: EdgeType,
 {
-    pub fn new(from: N, edges: &'a IndexMap<(N, N), E>, iter: Neighbors<'a, N, Ty>) -> Self {
+    pub fn new(from: N, edges: &'a IndexMap<(N, N), E, S>, iter: Neighbors<'a, N, Ty>) -> Self {
         Self { from, edges, iter }
     }
 }
 
-impl<'a, N, E, Ty> Iterator for Edges<'a, N, E, Ty>
+impl<'a, N, E, Ty, S> Iterator for Edges<'a, N, E, Ty, S>
 where
     N: 'a + NodeTrait,
     E: 'a,
     Ty: EdgeType,
+    S: BuildHasher,
 {
     type Item = (N, N, &'a E);
     fn next(&mut self) -> Option<Self::Item> {
@@ -66,6 +73,92 @@ where
             }
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, N, E, Ty, S> ExactSizeIterator for Edges<'a, N, E, Ty, S>
+where
+    N: 'a + NodeTrait,
+    E: 'a,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+}
+
+/// Iterator over the edges of a node in a given direction.
+///
+/// Iterator element type is `(N, N, &E)`, oriented as `(source, target, weight)`: the node is
+/// the source of `Outgoing` edges and the target of `Incoming` edges. A self loop is both.
+pub struct EdgesDirected<'a, N, E: 'a, Ty, S = RandomState>
+where
+    N: 'a + NodeTrait,
+    Ty: EdgeType,
+{
+    node: N,
+    dir: Direction,
+    edges: &'a IndexMap<(N, N), E, S>,
+    iter: SliceIter<'a, N>,
+    ty: PhantomData<Ty>,
+}
+
+impl<'a, N, E, Ty, S> EdgesDirected<'a, N, E, Ty, S>
+where
+    N: 'a + NodeTrait,
+    Ty: EdgeType,
+{
+    pub fn new(
+        node: N,
+        dir: Direction,
+        edges: &'a IndexMap<(N, N), E, S>,
+        iter: SliceIter<'a, N>,
+    ) -> Self {
+        Self {
+            node,
+            dir,
+            edges,
+            iter,
+            ty: PhantomData,
+        }
+    }
+}
+
+impl<'a, N, E, Ty, S> Iterator for EdgesDirected<'a, N, E, Ty, S>
+where
+    N: 'a + NodeTrait,
+    E: 'a,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    type Item = (N, N, &'a E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let other = *self.iter.next()?;
+        let (a, b) = match self.dir {
+            Outgoing => (self.node, other),
+            Incoming => (other, self.node),
+        };
+
+        match self.edges.get(&Graph::<N, E, Ty>::edge_key(a, b)) {
+            None => unreachable!(),
+            Some(weight) => Some((a, b, weight)),
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, N, E, Ty, S> ExactSizeIterator for EdgesDirected<'a, N, E, Ty, S>
+where
+    N: 'a + NodeTrait,
+    E: 'a,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
 }
 
 pub struct AllEdges<'a, N, E: 'a, Ty> {
@@ -173,6 +266,151 @@ where
     }
 }
 
+/// Iterator over all edges of a graph with mutable weights, in edge insertion order.
+///
+/// Iterator element type is `(N, N, &mut E)`.
+pub struct AllEdgesMut<'a, N, E: 'a, Ty> {
+    inner: IndexMapIterMut<'a, (N, N), E>,
+    ty: PhantomData<Ty>,
+}
+
+impl<'a, N, E, Ty> AllEdgesMut<'a, N, E, Ty>
+where
+    N: 'a + NodeTrait,
+{
+    pub fn new(inner: IndexMapIterMut<'a, (N, N), E>, ty: PhantomData<Ty>) -> Self {
+        Self { inner, ty }
+    }
+}
+
+impl<'a, N, E, Ty> Iterator for AllEdgesMut<'a, N, E, Ty>
+where
+    N: 'a + NodeTrait,
+    E: 'a,
+    Ty: EdgeType,
+{
+    type Item = (N, N, &'a mut E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|(&(a, b), weight)| (a, b, weight))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, N, E, Ty> DoubleEndedIterator for AllEdgesMut<'a, N, E, Ty>
+where
+    N: 'a + NodeTrait,
+    E: 'a,
+    Ty: EdgeType,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next_back()
+            .map(|(&(a, b), weight)| (a, b, weight))
+    }
+}
+
+/// Iterator over the edges starting from a node with mutable weights, in the order of
+/// `Graph::edges`.
+///
+/// Iterator element type is `(N, N, &mut E)`, the node being the source of every edge.
+pub struct EdgesMut<'a, N, E: 'a, Ty> {
+    from: N,
+    inner: VecIntoIter<(N, &'a mut E)>,
+    ty: PhantomData<Ty>,
+}
+
+impl<'a, N, E, Ty> EdgesMut<'a, N, E, Ty>
+where
+    N: 'a + NodeTrait,
+{
+    pub fn new(from: N, inner: Vec<(N, &'a mut E)>, ty: PhantomData<Ty>) -> Self {
+        Self {
+            from,
+            inner: inner.into_iter(),
+            ty,
+        }
+    }
+}
+
+impl<'a, N, E, Ty> Iterator for EdgesMut<'a, N, E, Ty>
+where
+    N: 'a + NodeTrait,
+    E: 'a,
+    Ty: EdgeType,
+{
+    type Item = (N, N, &'a mut E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let from = self.from;
+        self.inner.next().map(|(to, weight)| (from, to, weight))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<'a, N, E, Ty> DoubleEndedIterator for EdgesMut<'a, N, E, Ty>
+where
+    N: 'a + NodeTrait,
+    E: 'a,
+    Ty: EdgeType,
+{
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let from = self.from;
+        self.inner
+            .next_back()
+            .map(|(to, weight)| (from, to, weight))
+    }
+}
+
+impl<'a, N, E, Ty> ExactSizeIterator for EdgesMut<'a, N, E, Ty>
+where
+    N: 'a + NodeTrait,
+    E: 'a,
+    Ty: EdgeType,
+{
+}
+
+/// Consuming iterator over all edges of a graph, in edge insertion order.
+///
+/// Iterator element type is `(N, N, E)`.
+pub struct IntoAllEdges<N, E> {
+    inner: IndexMapIntoIter<(N, N), E>,
+}
+
+impl<N, E> IntoAllEdges<N, E> {
+    pub fn new(inner: IndexMapIntoIter<(N, N), E>) -> Self {
+        Self { inner }
+    }
+}
+
+impl<N, E> Iterator for IntoAllEdges<N, E> {
+    type Item = (N, N, E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.inner.next().map(|((a, b), weight)| (a, b, weight))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+impl<N, E> DoubleEndedIterator for IntoAllEdges<N, E> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.inner
+            .next_back()
+            .map(|((a, b), weight)| (a, b, weight))
+    }
+}
+
+impl<N, E> ExactSizeIterator for IntoAllEdges<N, E> {}
+
 /// Convert an element like `(i, j)` or `(i, j, w)` into a triple of source, target, edge weight.
 ///
 /// For `Graph::from_edges`.
@@ -304,7 +542,10 @@ impl PartialEq<Direction> for CompactDirection {
 
 #[cfg(test)]
 mod tests {
-    use crate::edge::{AllEdges, CompactDirection, Direction, EdgeType, Edges, IntoWeightedEdge};
+    use crate::edge::{
+        AllEdges, AllEdgesMut, CompactDirection, Direction, EdgeType, Edges, EdgesDirected,
+        EdgesMut, IntoAllEdges, IntoWeightedEdge,
+    };
     use crate::graph::{Directed, Undirected};
     use crate::traverse::Neighbors;
     use indexmap::IndexMap;
@@ -322,7 +563,7 @@ mod tests {
         // Prepare arguments.
         let from: u32 = 1;
         let edges: IndexMap<(u32, u32), f32> = IndexMap::new();
-        let node_neighbors: Vec<(u32, CompactDirection)> = vec![];
+        let node_neighbors: Vec<u32> = vec![];
         let iter = node_neighbors.iter();
         let neighbors: Neighbors<u32, Directed> = Neighbors::new(iter, PhantomData);
 
@@ -338,17 +579,13 @@ mod tests {
         edges.insert((2, 1), 2.0);
         edges.insert((1, 3), 3.0);
         edges.insert((1, 4), 4.0);
-        let node_neighbors: Vec<(u32, CompactDirection)> = vec![
-            (2, CompactDirection::Incoming),
-            (3, CompactDirection::Outgoing),
-            (4, CompactDirection::Outgoing),
-        ];
+        let node_neighbors: Vec<u32> = vec![3, 4];
         let neighbors: Neighbors<u32, Directed> =
             Neighbors::new(node_neighbors.iter(), PhantomData);
 
         // Construct edges from `1`.
-        // The edge (2, 1) is being filtered out as the edges are directed.
         let mut edges = Edges::new(from, &edges, neighbors);
+        assert_eq!(edges.len(), 2);
 
         // Test all existing edges from `1`.
         assert_eq!(edges.next(), Some((1, 3, &3.0)));
@@ -364,7 +601,7 @@ mod tests {
         // Prepare arguments.
         let from: u32 = 1;
         let edges: IndexMap<(u32, u32), f32> = IndexMap::new();
-        let node_neighbors: Vec<(u32, CompactDirection)> = vec![(2, CompactDirection::Incoming)];
+        let node_neighbors: Vec<u32> = vec![2];
         let neighbors: Neighbors<u32, Directed> =
             Neighbors::new(node_neighbors.iter(), PhantomData);
 
@@ -471,6 +708,75 @@ mod tests {
         assert_eq!(all_edges.next_back(), None);
     }
 
+    #[test]
+    fn edges_directed() {
+        let mut edges: IndexMap<(u32, u32), f32> = IndexMap::new();
+        edges.insert((1, 2), 1.0);
+        edges.insert((3, 1), 2.0);
+        edges.insert((1, 1), 3.0);
+        let (outgoing, incoming) = ([2, 1], [3, 1]);
+
+        let outgoing: EdgesDirected<u32, f32, Directed> =
+            EdgesDirected::new(1, Direction::Outgoing, &edges, outgoing.iter());
+        assert_eq!(
+            outgoing.collect::<Vec<_>>(),
+            vec![(1, 2, &1.0), (1, 1, &3.0)]
+        );
+
+        let incoming: EdgesDirected<u32, f32, Directed> =
+            EdgesDirected::new(1, Direction::Incoming, &edges, incoming.iter());
+        assert_eq!(
+            incoming.collect::<Vec<_>>(),
+            vec![(3, 1, &2.0), (1, 1, &3.0)]
+        );
+    }
+
+    #[test]
+    fn all_edges_mut() {
+        let mut edges: IndexMap<(u32, u32), f32> = IndexMap::new();
+        edges.insert((1, 2), 1.0);
+        edges.insert((2, 3), 2.0);
+
+        let mut all_edges_mut: AllEdgesMut<u32, f32, Directed> =
+            AllEdgesMut::new(edges.iter_mut(), PhantomData);
+
+        assert_eq!(all_edges_mut.size_hint(), (2, Some(2)));
+        assert_eq!(all_edges_mut.next_back(), Some((2, 3, &mut 2.0)));
+        if let Some((_, _, weight)) = all_edges_mut.next() {
+            *weight = 5.0;
+        }
+        assert_eq!(all_edges_mut.next(), None);
+        assert_eq!(edges[&(1, 2)], 5.0);
+    }
+
+    #[test]
+    fn edges_mut() {
+        let (mut a, mut b) = (1.0, 2.0);
+        let mut edges_mut =
+            EdgesMut::<u32, f32, Directed>::new(2, vec![(3, &mut a), (1, &mut b)], PhantomData);
+
+        assert_eq!(edges_mut.len(), 2);
+        assert_eq!(edges_mut.next_back(), Some((2, 1, &mut 2.0)));
+        assert_eq!(edges_mut.next(), Some((2, 3, &mut 1.0)));
+        assert_eq!(edges_mut.next(), None);
+    }
+
+    #[test]
+    fn into_all_edges() {
+        let mut edges: IndexMap<(u32, u32), String> = IndexMap::new();
+        edges.insert((1, 2), "a".to_string());
+        edges.insert((2, 3), "b".to_string());
+        edges.insert((3, 1), "c".to_string());
+
+        let mut into_all_edges = IntoAllEdges::new(edges.into_iter());
+
+        assert_eq!(into_all_edges.len(), 3);
+        assert_eq!(into_all_edges.next(), Some((1, 2, "a".to_string())));
+        assert_eq!(into_all_edges.next_back(), Some((3, 1, "c".to_string())));
+        assert_eq!(into_all_edges.next(), Some((2, 3, "b".to_string())));
+        assert_eq!(into_all_edges.next(), None);
+    }
+
     #[test]
     fn into_weighted_edge() {
         // Test with tuple.
@@ -0,0 +1,574 @@
+//! Generators of well known and random graphs.
+//!
+//! All generators produce `Graph<u32, (), Ty, S>` with nodes numbered from `0`, the edge type
+//! `Ty` and the hasher `S` being chosen by the caller. Edges of directed graphs point from the
+//! smaller node to the larger one, unless stated otherwise.
+//!
+//! Random generators are available with the `rand` feature. They are driven by a caller
+//! supplied `RngCore`, so seeding the generator makes the resulting graph reproducible.
+
+use crate::edge::EdgeType;
+use crate::graph::Graph;
+#[cfg(feature = "rand")]
+use rand_core::RngCore;
+#[cfg(feature = "rand")]
+use std::collections::HashSet;
+use std::hash::BuildHasher;
+
+/// Create a graph with nodes `0..n` and no edges.
+fn empty<Ty, S>(n: u32, edges: usize) -> Graph<u32, (), Ty, S>
+where
+    Ty: EdgeType,
+    S: BuildHasher + Default,
+{
+    let mut graph = Graph::with_capacity_default(n as usize, edges);
+    for i in 0..n {
+        graph.add_node(i);
+    }
+    graph
+}
+
+/// Complete graph with `n` nodes, every pair of distinct nodes being connected.
+///
+/// Directed graphs contain edges in both directions.
+pub fn complete<Ty, S>(n: u32) -> Graph<u32, (), Ty, S>
+where
+    Ty: EdgeType,
+    S: BuildHasher + Default,
+{
+    let mut graph = empty(n, (n as usize) * (n.saturating_sub(1) as usize));
+    for a in 0..n {
+        for b in 0..n {
+            if a < b || (Ty::is_directed() && a != b) {
+                graph.add_edge(a, b, ());
+            }
+        }
+    }
+    graph
+}
+
+/// Path graph `0 - 1 - ... - (n - 1)`.
+pub fn path<Ty, S>(n: u32) -> Graph<u32, (), Ty, S>
+where
+    Ty: EdgeType,
+    S: BuildHasher + Default,
+{
+    let mut graph = empty(n, n as usize);
+    for a in 1..n {
+        graph.add_edge(a - 1, a, ());
+    }
+    graph
+}
+
+/// Cycle graph `0 - 1 - ... - (n - 1) - 0`.
+///
+/// The cycle is closed only for `n >= 3`, smaller graphs are paths.
+pub fn cycle<Ty, S>(n: u32) -> Graph<u32, (), Ty, S>
+where
+    Ty: EdgeType,
+    S: BuildHasher + Default,
+{
+    let mut graph = path(n);
+    if n >= 3 {
+        graph.add_edge(n - 1, 0, ());
+    }
+    graph
+}
+
+/// Star graph with `n` nodes, the center `0` being connected to all other nodes.
+pub fn star<Ty, S>(n: u32) -> Graph<u32, (), Ty, S>
+where
+    Ty: EdgeType,
+    S: BuildHasher + Default,
+{
+    let mut graph = empty(n, n as usize);
+    for a in 1..n {
+        graph.add_edge(0, a, ());
+    }
+    graph
+}
+
+/// Wheel graph with `n` nodes, the hub `0` being connected to all nodes of the cycle
+/// `1 - 2 - ... - (n - 1) - 1`.
+///
+/// The rim cycle is closed only for `n >= 4`.
+pub fn wheel<Ty, S>(n: u32) -> Graph<u32, (), Ty, S>
+where
+    Ty: EdgeType,
+    S: BuildHasher + Default,
+{
+    let mut graph = star(n);
+    for a in 2..n {
+        graph.add_edge(a - 1, a, ());
+    }
+    if n >= 4 {
+        graph.add_edge(n - 1, 1, ());
+    }
+    graph
+}
+
+/// Two-dimensional grid graph with `rows * columns` nodes.
+///
+/// Node `r * columns + c` is in row `r` and column `c`, it is connected to its right and
+/// bottom neighbors.
+///
+/// # Panics
+///
+/// Panics if the number of nodes does not fit into `u32`.
+pub fn grid<Ty, S>(rows: u32, columns: u32) -> Graph<u32, (), Ty, S>
+where
+    Ty: EdgeType,
+    S: BuildHasher + Default,
+{
+    let n = rows.checked_mul(columns).unwrap_or_else(|| {
+        panic!(
+            "Grid of `{}` rows and `{}` columns is too large.",
+            rows, columns
+        )
+    });
+    let mut graph = empty(n, 2 * n as usize);
+    for r in 0..rows {
+        for c in 0..columns {
+            let a = r * columns + c;
+            if c + 1 < columns {
+                graph.add_edge(a, a + 1, ());
+            }
+            if r + 1 < rows {
+                graph.add_edge(a, a + columns, ());
+            }
+        }
+    }
+    graph
+}
+
+/// Hypercube graph of dimension `d` with `2^d` nodes, nodes being connected if their binary
+/// representations differ in exactly one bit.
+pub fn hypercube<Ty, S>(d: u32) -> Graph<u32, (), Ty, S>
+where
+    Ty: EdgeType,
+    S: BuildHasher + Default,
+{
+    assert!(d < 32, "Hypercube dimension `{}` is too large.", d);
+
+    let n = 1 << d;
+    let mut graph = empty(n, (n as usize) * (d as usize) / 2);
+    for a in 0..n {
+        for bit in 0..d {
+            let b = a | (1 << bit);
+            if a != b {
+                graph.add_edge(a, b, ());
+            }
+        }
+    }
+    graph
+}
+
+/// Complete binary tree with `n` nodes, node `i` being the parent of nodes `2i + 1` and
+/// `2i + 2`.
+pub fn binary_tree<Ty, S>(n: u32) -> Graph<u32, (), Ty, S>
+where
+    Ty: EdgeType,
+    S: BuildHasher + Default,
+{
+    let mut graph = empty(n, n as usize);
+    for a in 1..n {
+        graph.add_edge((a - 1) / 2, a, ());
+    }
+    graph
+}
+
+/// Petersen graph with 10 nodes and 15 edges.
+///
+/// Nodes `0..5` form the outer cycle, nodes `5..10` the inner pentagram and node `i` of the
+/// outer cycle is connected to node `i + 5`.
+pub fn petersen<Ty, S>() -> Graph<u32, (), Ty, S>
+where
+    Ty: EdgeType,
+    S: BuildHasher + Default,
+{
+    let mut graph = empty(10, 15);
+    for i in 0..5 {
+        graph.add_edge(i, (i + 1) % 5, ());
+        graph.add_edge(i, i + 5, ());
+        graph.add_edge(i + 5, (i + 2) % 5 + 5, ());
+    }
+    graph
+}
+
+/// Return a uniformly distributed random number in `0..n`.
+#[cfg(feature = "rand")]
+fn gen_below<R: RngCore>(rng: &mut R, n: u32) -> u32 {
+    // Reject the values of the incomplete last range to avoid modulo bias.
+    let zone = u32::MAX - (u32::MAX - n + 1) % n;
+    loop {
+        let value = rng.next_u32();
+        if value <= zone {
+            return value % n;
+        }
+    }
+}
+
+/// Return a uniformly distributed random number in `[0, 1)`.
+#[cfg(feature = "rand")]
+fn gen_unit<R: RngCore>(rng: &mut R) -> f64 {
+    (rng.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+}
+
+/// Erdős–Rényi random graph `G(n, p)` with `n` nodes, every possible edge being present with
+/// probability `p`.
+#[cfg(feature = "rand")]
+pub fn gnp<Ty, S, R>(n: u32, p: f64, rng: &mut R) -> Graph<u32, (), Ty, S>
+where
+    Ty: EdgeType,
+    S: BuildHasher + Default,
+    R: RngCore,
+{
+    let mut graph = empty(n, 0);
+    for a in 0..n {
+        for b in 0..n {
+            if (a < b || (Ty::is_directed() && a != b)) && gen_unit(rng) < p {
+                graph.add_edge(a, b, ());
+            }
+        }
+    }
+    graph
+}
+
+/// Erdős–Rényi random graph `G(n, m)` with `n` nodes and `m` edges chosen uniformly at random
+/// among all possible edges without self loops.
+///
+/// # Panics
+///
+/// Panics if `m` exceeds the number of possible edges.
+#[cfg(feature = "rand")]
+pub fn gnm<Ty, S, R>(n: u32, m: usize, rng: &mut R) -> Graph<u32, (), Ty, S>
+where
+    Ty: EdgeType,
+    S: BuildHasher + Default,
+    R: RngCore,
+{
+    let pairs = (n as usize) * (n.saturating_sub(1) as usize);
+    let max = if Ty::is_directed() { pairs } else { pairs / 2 };
+    assert!(
+        m <= max,
+        "Requested `{}` edges, but at most `{}` are possible.",
+        m,
+        max
+    );
+
+    let mut graph = empty(n, m);
+    while graph.edge_count() < m {
+        let a = gen_below(rng, n);
+        let b = gen_below(rng, n);
+        if a != b && !graph.contains_edge(a, b) {
+            graph.add_edge(a, b, ());
+        }
+    }
+    graph
+}
+
+/// Barabási–Albert preferential attachment graph with `n` nodes.
+///
+/// Starting with `m` isolated nodes, every new node is connected to `m` distinct existing
+/// nodes chosen with probability proportional to their degree. Edges point from the new node
+/// to the existing ones.
+///
+/// # Panics
+///
+/// Panics unless `1 <= m < n`.
+#[cfg(feature = "rand")]
+pub fn barabasi_albert<Ty, S, R>(n: u32, m: u32, rng: &mut R) -> Graph<u32, (), Ty, S>
+where
+    Ty: EdgeType,
+    S: BuildHasher + Default,
+    R: RngCore,
+{
+    assert!(
+        m >= 1 && m < n,
+        "Barabási–Albert requires `1 <= m < n`, got m = `{}` and n = `{}`.",
+        m,
+        n
+    );
+
+    let mut graph = empty(n, (n - m) as usize * m as usize);
+    // Every node is repeated once per each of its edges, giving the degree weighted choice.
+    let mut repeated: Vec<u32> = Vec::with_capacity(2 * graph.capacity().1);
+    let mut targets: Vec<u32> = (0..m).collect();
+
+    for source in m..n {
+        for &target in targets.iter() {
+            graph.add_edge(source, target, ());
+        }
+        repeated.extend(targets.iter().cloned());
+        repeated.extend((0..m).map(|_| source));
+
+        let mut chosen = HashSet::with_capacity(m as usize);
+        targets.clear();
+        while targets.len() < m as usize {
+            let target = repeated[gen_below(rng, repeated.len() as u32) as usize];
+            if chosen.insert(target) {
+                targets.push(target);
+            }
+        }
+    }
+    graph
+}
+
+/// Watts–Strogatz small-world graph with `n` nodes.
+///
+/// Starts with a ring lattice, every node being connected to its `k / 2` nearest neighbors on
+/// each side. Then the far end of every edge is rewired with probability `p` to a uniformly
+/// chosen node, avoiding self loops and duplicate edges.
+///
+/// # Panics
+///
+/// Panics unless `k < n`.
+#[cfg(feature = "rand")]
+pub fn watts_strogatz<Ty, S, R>(n: u32, k: u32, p: f64, rng: &mut R) -> Graph<u32, (), Ty, S>
+where
+    Ty: EdgeType,
+    S: BuildHasher + Default,
+    R: RngCore,
+{
+    assert!(
+        k < n,
+        "Watts–Strogatz requires `k < n`, got k = `{}` and n = `{}`.",
+        k,
+        n
+    );
+
+    let key = Graph::<u32, (), Ty>::edge_key;
+    let mut lattice: Vec<(u32, u32)> = Vec::with_capacity(n as usize * (k / 2) as usize);
+    for j in 1..=k / 2 {
+        for a in 0..n {
+            lattice.push((a, ((u64::from(a) + u64::from(j)) % u64::from(n)) as u32));
+        }
+    }
+    let mut edges: HashSet<(u32, u32)> = lattice.iter().map(|&(a, b)| key(a, b)).collect();
+    // Number of rewiring candidates already taken by every node.
+    let mut taken = vec![0; n as usize];
+    for &(a, b) in lattice.iter() {
+        taken[a as usize] += 1;
+        if !Ty::is_directed() {
+            taken[b as usize] += 1;
+        }
+    }
+
+    for (a, b) in lattice.iter_mut() {
+        if gen_unit(rng) >= p {
+            continue;
+        }
+        // Node `a` may already be connected to all other nodes.
+        if taken[*a as usize] >= n - 1 {
+            continue;
+        }
+
+        let c = loop {
+            let c = gen_below(rng, n);
+            if c != *a && !edges.contains(&key(*a, c)) {
+                break c;
+            }
+        };
+        edges.remove(&key(*a, *b));
+        edges.insert(key(*a, c));
+        if !Ty::is_directed() {
+            taken[*b as usize] -= 1;
+            taken[c as usize] += 1;
+        }
+        *b = c;
+    }
+
+    let mut graph = empty(n, lattice.len());
+    for (a, b) in lattice {
+        graph.add_edge(a, b, ());
+    }
+    graph
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edge::Direction::{Incoming, Outgoing};
+    use crate::generators;
+    use crate::graph::{Directed, Graph, Undirected};
+    #[cfg(feature = "rand")]
+    use rand_core::SeedableRng;
+    #[cfg(feature = "rand")]
+    use rand_pcg::Pcg32;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::BuildHasherDefault;
+
+    #[test]
+    fn complete() {
+        let graph: Graph<u32, (), Undirected> = generators::complete(5);
+
+        assert_eq!(graph.node_count(), 5);
+        assert_eq!(graph.edge_count(), 10);
+        assert!(graph.contains_edge(4, 0));
+
+        let graph: Graph<u32, (), Directed> = generators::complete(5);
+
+        assert_eq!(graph.edge_count(), 20);
+        assert!(graph.contains_edge(4, 0));
+        assert!(graph.contains_edge(0, 4));
+    }
+
+    #[test]
+    fn path_and_cycle() {
+        let graph: Graph<u32, (), Directed> = generators::path(4);
+
+        assert_eq!(graph.edge_count(), 3);
+        assert!(graph.contains_edge(2, 3));
+
+        let graph: Graph<u32, (), Directed> = generators::cycle(4);
+
+        assert_eq!(graph.edge_count(), 4);
+        assert!(graph.contains_edge(3, 0));
+
+        let graph: Graph<u32, (), Undirected> = generators::cycle(2);
+
+        assert_eq!(graph.edge_count(), 1);
+    }
+
+    #[test]
+    fn star_and_wheel() {
+        let graph: Graph<u32, (), Undirected> = generators::star(5);
+
+        assert_eq!(graph.node_count(), 5);
+        assert_eq!(graph.neighbors(0).count(), 4);
+        assert_eq!(graph.edge_count(), 4);
+
+        let graph: Graph<u32, (), Undirected> = generators::wheel(5);
+
+        assert_eq!(graph.node_count(), 5);
+        assert_eq!(graph.edge_count(), 8);
+        assert!(graph.contains_edge(4, 1));
+        assert!(graph.neighbors(1).all(|n| n == 0 || n == 2 || n == 4));
+    }
+
+    #[test]
+    fn grid() {
+        let graph: Graph<u32, (), Undirected> = generators::grid(3, 4);
+
+        assert_eq!(graph.node_count(), 12);
+        // 3 rows of 3 horizontal edges and 4 columns of 2 vertical edges.
+        assert_eq!(graph.edge_count(), 17);
+        assert!(graph.contains_edge(5, 6));
+        assert!(graph.contains_edge(5, 9));
+        assert!(!graph.contains_edge(3, 4));
+    }
+
+    #[test]
+    #[should_panic]
+    fn grid_too_large() {
+        let _graph: Graph<u32, (), Undirected> = generators::grid(1 << 16, 1 << 16);
+    }
+
+    #[test]
+    fn with_hasher() {
+        type Hasher = BuildHasherDefault<DefaultHasher>;
+
+        let graph: Graph<u32, (), Undirected, Hasher> = generators::cycle(5);
+
+        assert_eq!(graph.edge_count(), 5);
+        assert!(graph.contains_edge(0, 4));
+    }
+
+    #[test]
+    fn hypercube() {
+        let graph: Graph<u32, (), Undirected> = generators::hypercube(3);
+
+        assert_eq!(graph.node_count(), 8);
+        assert_eq!(graph.edge_count(), 12);
+        assert!(graph.nodes().all(|n| graph.neighbors(n).count() == 3));
+    }
+
+    #[test]
+    fn binary_tree() {
+        let graph: Graph<u32, (), Directed> = generators::binary_tree(7);
+
+        assert_eq!(graph.edge_count(), 6);
+        assert_eq!(graph.neighbors(2).collect::<Vec<_>>(), vec![5, 6]);
+        assert_eq!(graph.neighbors_directed(0, Incoming).count(), 0);
+        assert_eq!(graph.neighbors_directed(6, Outgoing).count(), 0);
+    }
+
+    #[test]
+    fn petersen() {
+        let graph: Graph<u32, (), Undirected> = generators::petersen();
+
+        assert_eq!(graph.node_count(), 10);
+        assert_eq!(graph.edge_count(), 15);
+        assert!(graph.nodes().all(|n| graph.neighbors(n).count() == 3));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn gnp() {
+        let mut rng = Pcg32::seed_from_u64(7);
+        let empty: Graph<u32, (), Undirected> = generators::gnp(10, 0.0, &mut rng);
+        let full: Graph<u32, (), Directed> = generators::gnp(10, 1.0, &mut rng);
+
+        assert_eq!(empty.node_count(), 10);
+        assert_eq!(empty.edge_count(), 0);
+        assert_eq!(full.edge_count(), 90);
+
+        // The same seed gives the same graph.
+        let a: Graph<u32, (), Undirected> = generators::gnp(20, 0.3, &mut Pcg32::seed_from_u64(1));
+        let b: Graph<u32, (), Undirected> = generators::gnp(20, 0.3, &mut Pcg32::seed_from_u64(1));
+
+        assert_eq!(
+            a.all_edges().collect::<Vec<_>>(),
+            b.all_edges().collect::<Vec<_>>()
+        );
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn gnm() {
+        let mut rng = Pcg32::seed_from_u64(7);
+        let graph: Graph<u32, (), Undirected> = generators::gnm(10, 20, &mut rng);
+
+        assert_eq!(graph.node_count(), 10);
+        assert_eq!(graph.edge_count(), 20);
+        assert!(graph.all_edges().all(|(a, b, _)| a != b));
+
+        let graph: Graph<u32, (), Undirected> = generators::gnm(5, 10, &mut rng);
+
+        assert_eq!(graph.edge_count(), 10);
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    #[should_panic]
+    fn gnm_too_many_edges() {
+        let _graph: Graph<u32, (), Undirected> =
+            generators::gnm(5, 11, &mut Pcg32::seed_from_u64(7));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn barabasi_albert() {
+        let mut rng = Pcg32::seed_from_u64(7);
+        let graph: Graph<u32, (), Undirected> = generators::barabasi_albert(100, 3, &mut rng);
+
+        assert_eq!(graph.node_count(), 100);
+        assert_eq!(graph.edge_count(), 97 * 3);
+        assert!((3..100).all(|n| graph.neighbors(n).count() >= 3));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn watts_strogatz() {
+        let mut rng = Pcg32::seed_from_u64(7);
+        let lattice: Graph<u32, (), Undirected> = generators::watts_strogatz(10, 4, 0.0, &mut rng);
+
+        assert_eq!(lattice.edge_count(), 20);
+        assert!(lattice.contains_edge(9, 1));
+        assert!(lattice.nodes().all(|n| lattice.neighbors(n).count() == 4));
+
+        let rewired: Graph<u32, (), Undirected> = generators::watts_strogatz(30, 4, 0.5, &mut rng);
+
+        assert_eq!(rewired.edge_count(), 60);
+        assert!(rewired.all_edges().all(|(a, b, _)| a != b));
+    }
+}
@@ -5,14 +5,23 @@
 
 pub use crate::node::NodeTrait;
 
-use crate::edge::{AllEdges, CompactDirection, Direction, EdgeType, Edges, IntoWeightedEdge};
-use crate::node::Nodes;
+use crate::csr::Csr;
+use crate::edge::{
+    AllEdges, AllEdgesMut,
+    Direction::{self, Incoming, Outgoing},
+    EdgeType, Edges, EdgesDirected, EdgesMut, IntoAllEdges, IntoWeightedEdge,
+};
+use crate::node::{Adjacency, Externals, NodeWeights, NodeWeightsMut, Nodes};
 use crate::traverse::{Neighbors, NeighborsDirected};
 use indexmap::IndexMap;
+use std::collections::hash_map::RandomState;
+use std::collections::HashSet;
 use std::fmt;
-use std::hash::Hash;
+use std::hash::{BuildHasher, Hash};
 use std::iter::FromIterator;
 use std::marker::PhantomData;
+use std::mem;
+use std::ops::{Index, IndexMut};
 
 /// Marker type for a directed graph.
 #[derive(Copy, Debug)]
@@ -28,14 +37,16 @@ copyclone!(Undirected);
 ///
 /// For example, an edge between *1* and *2* is equivalent to an edge between
 /// *2* and *1*.
-pub type UndirectedGraph<N, E> = Graph<N, E, Undirected>;
+pub type UndirectedGraph<N, E, S = RandomState> = Graph<N, E, Undirected, S>;
 
-/// `Graph<N, E, Ty>` is a graph datastructure using an associative array
+/// `Graph<N, E, Ty, S>` is a graph datastructure using an associative array
 /// of its node weights `N`.
 ///
 /// It uses an combined adjacency list and sparse adjacency matrix
 /// representation, using **O(|V| + |E|)** space, and allows testing for edge
-/// existance in constant time.
+/// existance in constant time. The outgoing and incoming neighbors of a node are
+/// kept in separate lists, so iterating over the neighbors in one direction doesn't
+/// visit the other.
 ///
 /// # `Graph` is parameterized over:
 ///
@@ -48,18 +59,34 @@ pub type UndirectedGraph<N, E> = Graph<N, E, Undirected>;
 /// - `E` can be of arbitrary type.
 /// - Edge type `Ty` that determines whether the graph edges are directed or
 /// undirected.
+/// - Hasher `S` of the node and edge maps, the `RandomState` of the standard library by
+/// default. A faster or deterministic hasher can be used with `with_hasher`.
+/// - Data `W` attached to every node, `()` by default. It is used by `NodeWeightedGraph`,
+/// which keeps the nodes and their data in the same entry.
 ///
 /// You can use the type alias `UndirectedGraph` for convenience.
 ///
-/// `Graph` does not allow parallel edges, but self loops are allowed.
+/// `Graph` does not allow parallel edges, but self loops are allowed. Use `MultiGraph` for
+/// parallel edges.
+///
+/// Every edge records its positions in the adjacency lists of its nodes, so removing an edge
+/// takes constant time.
 #[derive(Clone)]
-pub struct Graph<N, E, Ty = Directed> {
-    nodes: IndexMap<N, Vec<(N, CompactDirection)>>,
-    edges: IndexMap<(N, N), E>,
+pub struct Graph<N, E, Ty = Directed, S = RandomState, W = ()> {
+    nodes: IndexMap<N, Adjacency<N, W>, S>,
+    edges: IndexMap<(N, N), E, S>,
+    /// Positions of every edge in the adjacency lists of the first and the second node of its
+    /// key, following the order of `edges`.
+    positions: Vec<[usize; 2]>,
     ty: PhantomData<Ty>,
 }
 
-impl<N: Eq + Hash + fmt::Debug, E: fmt::Debug, Ty: EdgeType> fmt::Debug for Graph<N, E, Ty> {
+impl<N, E, Ty, S, W> fmt::Debug for Graph<N, E, Ty, S, W>
+where
+    N: Eq + Hash + fmt::Debug,
+    E: fmt::Debug,
+    Ty: EdgeType,
+{
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         self.nodes.fmt(f)
     }
@@ -84,33 +111,55 @@ where
 
     /// Create a new `Graph` with estimated capacity.
     pub fn with_capacity(nodes: usize, edges: usize) -> Self {
-        Self {
-            nodes: IndexMap::with_capacity(nodes),
-            edges: IndexMap::with_capacity(edges),
-            ty: PhantomData,
-        }
+        Self::with_capacity_and_hasher(nodes, edges, RandomState::new())
     }
+}
 
-    /// Return the current node and edge capacity of the graph.
-    pub fn capacity(&self) -> (usize, usize) {
-        (self.nodes.capacity(), self.edges.capacity())
+impl<N, E, Ty, S> Graph<N, E, Ty, S>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    /// Create a new `Graph` using `hash_builder` to hash the nodes and edges.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use safe_graph::graph::Directed;
+    /// use safe_graph::Graph;
+    /// use std::collections::hash_map::DefaultHasher;
+    /// use std::hash::BuildHasherDefault;
+    ///
+    /// // Hash deterministically, without random keys.
+    /// type DeterministicGraph = Graph<u32, (), Directed, BuildHasherDefault<DefaultHasher>>;
+    ///
+    /// let mut graph = DeterministicGraph::with_hasher(BuildHasherDefault::default());
+    /// graph.add_edge(1, 2, ());
+    ///
+    /// assert!(graph.contains_edge(1, 2));
+    /// ```
+    pub fn with_hasher(hash_builder: S) -> Self
+    where
+        S: Clone,
+    {
+        Self::with_capacity_and_hasher(0, 0, hash_builder)
     }
 
-    /// Use their natural order to map the node pair (a, b) to a canonical edge id.
-    #[inline]
-    pub fn edge_key(a: N, b: N) -> (N, N) {
-        if Ty::is_directed() || a <= b {
-            (a, b)
-        } else {
-            (b, a)
+    /// Create a new `Graph` with estimated capacity, using `hash_builder` to hash the nodes
+    /// and edges.
+    pub fn with_capacity_and_hasher(nodes: usize, edges: usize, hash_builder: S) -> Self
+    where
+        S: Clone,
+    {
+        Self {
+            nodes: IndexMap::with_capacity_and_hasher(nodes, hash_builder.clone()),
+            edges: IndexMap::with_capacity_and_hasher(edges, hash_builder),
+            positions: Vec::with_capacity(edges),
+            ty: PhantomData,
         }
     }
 
-    /// Whether the graph has directed edges.
-    pub fn is_directed(&self) -> bool {
-        Ty::is_directed()
-    }
-
     /// Create a new `Graph` from an iterable of edges.
     ///
     /// Node values are taken directly from the list.
@@ -136,10 +185,110 @@ where
     where
         I: IntoIterator,
         I::Item: IntoWeightedEdge<E, NodeId = N>,
+        S: Default,
     {
         Self::from_iter(iterable)
     }
 
+    /// Create a new `Graph` from a list of nodes and an iterable of edges.
+    ///
+    /// Nodes are inserted first, in their order, so isolated nodes are kept. Nodes of the edges
+    /// missing from the list are inserted automatically after them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use safe_graph::Graph;
+    ///
+    /// let graph: Graph<_, _> = Graph::from_nodes_edges(vec![3, 1, 2], vec![(1, 2, 0.5)]);
+    ///
+    /// assert_eq!(graph.nodes().collect::<Vec<_>>(), vec![3, 1, 2]);
+    /// assert_eq!(graph.edge_weight(1, 2), Some(&0.5));
+    /// ```
+    pub fn from_nodes_edges<I, J>(nodes: I, edges: J) -> Self
+    where
+        I: IntoIterator<Item = N>,
+        J: IntoIterator,
+        J::Item: IntoWeightedEdge<E, NodeId = N>,
+        S: Default,
+    {
+        let mut graph = Self::default();
+        for n in nodes {
+            graph.add_node(n);
+        }
+        graph.extend(edges);
+        graph
+    }
+}
+
+impl<N, E, Ty, S, W> Graph<N, E, Ty, S, W>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    /// Create a new `Graph` with estimated capacity, using the default hasher of type `S`.
+    pub(crate) fn with_capacity_default(nodes: usize, edges: usize) -> Self
+    where
+        S: Default,
+    {
+        Self {
+            nodes: IndexMap::with_capacity_and_hasher(nodes, S::default()),
+            edges: IndexMap::with_capacity_and_hasher(edges, S::default()),
+            positions: Vec::with_capacity(edges),
+            ty: PhantomData,
+        }
+    }
+
+    /// Return a reference to the hasher of the graph.
+    pub fn hasher(&self) -> &S {
+        self.edges.hasher()
+    }
+
+    /// Return the current node and edge capacity of the graph.
+    pub fn capacity(&self) -> (usize, usize) {
+        (self.nodes.capacity(), self.edges.capacity())
+    }
+
+    /// Use their natural order to map the node pair (a, b) to a canonical edge id.
+    #[inline]
+    pub fn edge_key(a: N, b: N) -> (N, N) {
+        if Ty::is_directed() || a <= b {
+            (a, b)
+        } else {
+            (b, a)
+        }
+    }
+
+    /// Whether the graph has directed edges.
+    pub fn is_directed(&self) -> bool {
+        Ty::is_directed()
+    }
+
+    /// Convert the graph into its list of nodes and list of edges, both in insertion order.
+    ///
+    /// `Graph::from_nodes_edges` rebuilds the same graph from them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use safe_graph::Graph;
+    ///
+    /// let mut graph = Graph::<_, _>::from_edges(&[(1, 2, "a".to_string())]);
+    /// graph.add_node(3);
+    ///
+    /// let (nodes, edges) = graph.into_nodes_edges();
+    ///
+    /// assert_eq!(nodes, vec![1, 2, 3]);
+    /// assert_eq!(edges, vec![(1, 2, "a".to_string())]);
+    /// ```
+    pub fn into_nodes_edges(self) -> (Vec<N>, Vec<(N, N, E)>) {
+        let nodes = self.nodes.keys().cloned().collect();
+        let edges = self.into_iter().collect();
+
+        (nodes, edges)
+    }
+
     /// Return the number of nodes in the graph.
     pub fn node_count(&self) -> usize {
         self.nodes.len()
@@ -154,14 +303,72 @@ where
     pub fn clear(&mut self) {
         self.nodes.clear();
         self.edges.clear();
+        self.positions.clear();
     }
 
     /// Add node `n` to the graph.
-    pub fn add_node(&mut self, n: N) -> N {
-        self.nodes.entry(n).or_insert(Vec::new());
+    pub fn add_node(&mut self, n: N) -> N
+    where
+        W: Default,
+    {
+        self.nodes.entry(n).or_default();
         n
     }
 
+    /// Add node `n` with data `weight` to the graph, and return the old data of an existing
+    /// node.
+    pub(crate) fn add_node_with(&mut self, n: N, weight: W) -> Option<W> {
+        match self.nodes.get_mut(&n) {
+            Some(adjacency) => Some(mem::replace(adjacency.weight_mut(), weight)),
+            None => {
+                self.nodes.insert(n, Adjacency::new(weight));
+                None
+            }
+        }
+    }
+
+    /// Return a reference to the data of node `n`.
+    pub(crate) fn node_weight(&self, n: N) -> Option<&W> {
+        self.nodes.get(&n).map(Adjacency::weight)
+    }
+
+    /// Return a mutable reference to the data of node `n`.
+    pub(crate) fn node_weight_mut(&mut self, n: N) -> Option<&mut W> {
+        self.nodes.get_mut(&n).map(Adjacency::weight_mut)
+    }
+
+    /// Return an iterator over the nodes paired with their data, in node insertion order.
+    pub(crate) fn node_weights(&self) -> NodeWeights<'_, N, W> {
+        NodeWeights::new(self.nodes.iter())
+    }
+
+    /// Return an iterator over the nodes paired with their mutable data, in node insertion
+    /// order.
+    pub(crate) fn node_weights_mut(&mut self) -> NodeWeightsMut<'_, N, W> {
+        NodeWeightsMut::new(self.nodes.iter_mut())
+    }
+
+    /// Convert the graph by replacing the data of every node by `f(n, data)`, keeping the
+    /// nodes and edges.
+    pub(crate) fn map_node_weights<W2, F>(self, mut f: F) -> Graph<N, E, Ty, S, W2>
+    where
+        F: FnMut(N, W) -> W2,
+        S: Clone,
+    {
+        let hash_builder = self.edges.hasher().clone();
+        let mut nodes = IndexMap::with_capacity_and_hasher(self.nodes.len(), hash_builder);
+        for (n, adjacency) in self.nodes {
+            nodes.insert(n, adjacency.map_weight(|weight| f(n, weight)));
+        }
+
+        Graph {
+            nodes,
+            edges: self.edges,
+            positions: self.positions,
+            ty: PhantomData,
+        }
+    }
+
     /// Return `true` if the node is contained in the graph.
     pub fn contains_node(&self, n: N) -> bool {
         self.nodes.contains_key(&n)
@@ -190,145 +397,841 @@ where
     /// assert!(g.contains_edge("x", "y"));
     /// assert!(!g.contains_edge("y", "x"));
     /// ```
-    pub fn add_edge(&mut self, a: N, b: N, weight: E) -> Option<E> {
-        if let old @ Some(_) = self.edges.insert(Self::edge_key(a, b), weight) {
+    pub fn add_edge(&mut self, a: N, b: N, weight: E) -> Option<E>
+    where
+        W: Default,
+    {
+        self.add_edge_with(a, b, weight, W::default)
+    }
+
+    /// Add an edge like `add_edge`, missing nodes `a` and/or `b` getting their data from
+    /// `node`.
+    pub(crate) fn add_edge_with<F>(&mut self, a: N, b: N, weight: E, mut node: F) -> Option<E>
+    where
+        F: FnMut() -> W,
+    {
+        let key = Self::edge_key(a, b);
+        if let old @ Some(_) = self.edges.insert(key, weight) {
             old
         } else {
-            // Insert in the adjacency list if it's a new edge.
-            self.nodes
-                .entry(a)
-                .or_insert_with(|| Vec::with_capacity(1))
-                .push((b, CompactDirection::Outgoing));
-
-            // Self loops don't have the Incoming entry.
-            if a != b {
+            // Insert in the adjacency lists if it's a new edge.
+            let mut push = |n, dir, m| {
                 self.nodes
-                    .entry(b)
-                    .or_insert_with(|| Vec::with_capacity(1))
-                    .push((a, CompactDirection::Incoming));
-            }
-
+                    .entry(n)
+                    .or_insert_with(|| Adjacency::new(node()))
+                    .push(dir, m)
+            };
+            let i = push(a, Outgoing, b);
+
+            // Undirected self loops are listed once.
+            let j = if Ty::is_directed() {
+                push(b, Incoming, a)
+            } else if a != b {
+                push(b, Outgoing, a)
+            } else {
+                i
+            };
+
+            self.positions
+                .push(if key.0 == a { [i, j] } else { [j, i] });
             None
         }
     }
 
+    /// Remove the edge connecting `a` with `b` and return its weight, or `None` if the edge
+    /// does not exist. The nodes are kept.
+    ///
+    /// Removal takes constant time: the last edge takes the index of the removed one, and the
+    /// last neighbor of each node takes the place of the removed neighbor in its adjacency
+    /// list.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use safe_graph::Graph;
+    ///
+    /// let mut graph = Graph::<_, _>::from_edges(&[(1, 2, 0.5), (1, 3, 1.5), (1, 4, 2.5)]);
+    ///
+    /// assert_eq!(graph.remove_edge(1, 2), Some(0.5));
+    /// assert_eq!(graph.remove_edge(1, 2), None);
+    ///
+    /// assert_eq!(graph.edge_count(), 2);
+    /// assert_eq!(graph.node_count(), 4);
+    /// assert_eq!(graph.neighbors(1).collect::<Vec<_>>(), vec![4, 3]);
+    /// ```
+    pub fn remove_edge(&mut self, a: N, b: N) -> Option<E> {
+        let (i, (first, second), weight) = self.edges.swap_remove_full(&Self::edge_key(a, b))?;
+        let [p, q] = self.positions.swap_remove(i);
+
+        // Undirected self loops are listed once.
+        self.remove_adjacent(first, Outgoing, p);
+        if Ty::is_directed() {
+            self.remove_adjacent(second, Incoming, q);
+        } else if first != second {
+            self.remove_adjacent(second, Outgoing, q);
+        }
+
+        Some(weight)
+    }
+
+    /// Remove the neighbor at position `i` in the adjacency list of `n`, updating the position
+    /// of the edge of the neighbor moved in its place.
+    fn remove_adjacent(&mut self, n: N, dir: Direction, i: usize) {
+        let neighbors = self
+            .nodes
+            .get_mut(&n)
+            .expect("Edge node is not part of the graph.");
+
+        if let Some(m) = neighbors.swap_remove(dir, i) {
+            let key = match dir {
+                Outgoing => Self::edge_key(n, m),
+                Incoming => Self::edge_key(m, n),
+            };
+            let j = self
+                .edges
+                .get_index_of(&key)
+                .expect("Adjacent edge is not part of the graph.");
+
+            let positions = &mut self.positions[j];
+            if Ty::is_directed() {
+                match dir {
+                    Outgoing => positions[0] = i,
+                    Incoming => positions[1] = i,
+                }
+            } else {
+                // An undirected self loop is listed once, at both positions.
+                if key.0 == n {
+                    positions[0] = i;
+                }
+                if key.1 == n {
+                    positions[1] = i;
+                }
+            }
+        }
+    }
+
     /// Return `true` if the edge connecting `a` with `b` is contained in the graph.
     pub fn contains_edge(&self, a: N, b: N) -> bool {
         self.edges.contains_key(&Self::edge_key(a, b))
     }
 
+    /// Return the index of node `n`, its position in the insertion order of nodes, or `None`
+    /// if the node does not exist in the graph.
+    ///
+    /// Indices are contiguous from `0` to `node_count() - 1`, so they can index side tables
+    /// stored as `Vec`s.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use safe_graph::Graph;
+    ///
+    /// let graph = Graph::<_, ()>::from_edges(&[("a", "b"), ("c", "a")]);
+    ///
+    /// assert_eq!(graph.node_index("c"), Some(2));
+    /// assert_eq!(graph.node_at(2), Some("c"));
+    /// ```
+    pub fn node_index(&self, n: N) -> Option<usize> {
+        self.nodes.get_index_of(&n)
+    }
+
+    /// Return the node at index `i`, or `None` if the index is out of bounds.
+    pub fn node_at(&self, i: usize) -> Option<N> {
+        self.nodes.get_index(i).map(|(&n, _)| n)
+    }
+
+    /// Return the index of the edge connecting `a` with `b`, its position in the insertion
+    /// order of edges, or `None` if the edge does not exist in the graph.
+    ///
+    /// Indices are contiguous from `0` to `edge_count() - 1`. Removing an edge moves the last
+    /// edge to the index of the removed one.
+    pub fn edge_index(&self, a: N, b: N) -> Option<usize> {
+        self.edges.get_index_of(&Self::edge_key(a, b))
+    }
+
+    /// Return the edge at index `i` with its weight, or `None` if the index is out of bounds.
+    ///
+    /// The nodes of an undirected edge are in their natural order, like `all_edges`.
+    pub fn edge_at(&self, i: usize) -> Option<(N, N, &E)> {
+        self.edges
+            .get_index(i)
+            .map(|(&(a, b), weight)| (a, b, weight))
+    }
+
+    /// Return the neighbors of node `n` in direction `dir`, empty if the node doesn't exist.
+    ///
+    /// Undirected graphs list all neighbors whatever the direction.
+    pub(crate) fn adjacency(&self, n: N, dir: Direction) -> &[N] {
+        let dir = if Ty::is_directed() { dir } else { Outgoing };
+        self.nodes
+            .get(&n)
+            .map_or(&[], |neighbors| neighbors.get(dir))
+    }
+
     /// Return an iterator over the nodes of the graph.
     ///
     /// Iterator element type is `N`.
-    pub fn nodes(&self) -> Nodes<N> {
+    pub fn nodes(&self) -> Nodes<N, W> {
         Nodes::new(self.nodes.keys().cloned())
     }
 
-    /// Return an iterator of all nodes with an edge starting from `a`.
+    /// Return the number of edges of node `n`, a self loop being counted twice.
+    ///
+    /// For a directed graph, this is the sum of `in_degree` and `out_degree`.
+    /// Return `0` if the node doesn't exist.
+    pub fn degree(&self, n: N) -> usize {
+        if Ty::is_directed() {
+            self.in_degree(n) + self.out_degree(n)
+        } else {
+            // Undirected self loops are listed once.
+            self.adjacency(n, Outgoing).len() + self.contains_edge(n, n) as usize
+        }
+    }
+
+    /// Return the number of edges to node `n`, a self loop being counted once.
+    ///
+    /// For an undirected graph, this is equal to `degree`.
+    /// Return `0` if the node doesn't exist.
+    pub fn in_degree(&self, n: N) -> usize {
+        if !Ty::is_directed() {
+            return self.degree(n);
+        }
+
+        self.adjacency(n, Incoming).len()
+    }
+
+    /// Return the number of edges from node `n`, a self loop being counted once.
+    ///
+    /// For an undirected graph, this is equal to `degree`.
+    /// Return `0` if the node doesn't exist.
+    pub fn out_degree(&self, n: N) -> usize {
+        if !Ty::is_directed() {
+            return self.degree(n);
+        }
+
+        self.adjacency(n, Outgoing).len()
+    }
+
+    /// Return the number of nodes of every degree, indexed by the degree.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use safe_graph::UndirectedGraph;
+    ///
+    /// let graph = UndirectedGraph::<_, ()>::from_edges(&[(1, 2), (1, 3), (1, 4)]);
+    ///
+    /// // Three nodes of degree `1` and one of degree `3`.
+    /// assert_eq!(graph.degree_histogram(), vec![0, 3, 0, 1]);
+    /// ```
+    pub fn degree_histogram(&self) -> Vec<usize> {
+        let mut histogram = vec![0; self.max_degree() + 1];
+        for n in self.nodes() {
+            histogram[self.degree(n)] += 1;
+        }
+        histogram
+    }
+
+    /// Return the highest `degree` of the nodes, `0` for an empty graph.
+    pub fn max_degree(&self) -> usize {
+        self.nodes().map(|n| self.degree(n)).max().unwrap_or(0)
+    }
+
+    /// Return the ratio of the number of edges to the maximal number of edges between distinct
+    /// nodes.
+    ///
+    /// Self loops are counted as regular edges, so the density may exceed `1.0` with self
+    /// loops. Return `0.0` for a graph of less than two nodes.
+    pub fn density(&self) -> f64 {
+        let nodes = self.node_count() as f64;
+        if nodes < 2.0 {
+            return 0.0;
+        }
+
+        let max_edges = nodes * (nodes - 1.0);
+        if Ty::is_directed() {
+            self.edge_count() as f64 / max_edges
+        } else {
+            2.0 * self.edge_count() as f64 / max_edges
+        }
+    }
+
+    /// Return an iterator of the nodes without an edge in the specified direction.
+    ///
+    /// - `Directed`, `Outgoing`: Nodes without outgoing edges.
+    /// - `Directed`, `Incoming`: Nodes without incoming edges.
+    /// - `Undirected`: Nodes without any edge.
+    ///
+    /// A self loop is both an outgoing and an incoming edge.<br>
+    /// Iterator element type is `N`.
+    pub fn externals(&self, dir: Direction) -> Externals<'_, N, Ty, W> {
+        Externals::new(self.nodes.iter(), dir)
+    }
+
+    /// Return an iterator of the nodes without incoming edges.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use safe_graph::Graph;
+    ///
+    /// let graph = Graph::<_, ()>::from_edges(&[(1, 2), (3, 2), (2, 4)]);
+    ///
+    /// assert_eq!(graph.sources().collect::<Vec<_>>(), vec![1, 3]);
+    /// assert_eq!(graph.sinks().collect::<Vec<_>>(), vec![4]);
+    /// ```
+    pub fn sources(&self) -> Externals<'_, N, Ty, W> {
+        self.externals(Direction::Incoming)
+    }
+
+    /// Return an iterator of the nodes without outgoing edges.
+    pub fn sinks(&self) -> Externals<'_, N, Ty, W> {
+        self.externals(Direction::Outgoing)
+    }
+
+    /// Return an iterator of all nodes with an edge starting from `a`.
+    ///
+    /// - `Directed`: Outgoing edges from `a`.
+    /// - `Undirected`: All edges from or to `a`.
+    ///
+    /// Produces an empty iterator if the node doesn't exist.<br>
+    /// Iterator element type is `N`, the iterator is an `ExactSizeIterator`.
+    pub fn neighbors(&self, a: N) -> Neighbors<N, Ty> {
+        Neighbors::new(self.adjacency(a, Outgoing).iter(), self.ty)
+    }
+
+    /// Return an iterator of all neighbors that have an edge between them and
+    /// `a`, in the specified direction.
+    /// If the graph's edges are undirected, this is equivalent to *.neighbors(a)*.
+    ///
+    /// - `Directed`, `Outgoing`: All edges from `a`.
+    /// - `Directed`, `Incoming`: All edges to `a`.
+    /// - `Undirected`: All edges from or to `a`.
+    ///
+    /// A self loop is both an `Outgoing` and an `Incoming` edge, i.e. a directed self loop
+    /// makes `a` its own `Incoming` neighbor as well.
+    /// Produces an empty iterator if the node doesn't exist.<br>
+    /// Iterator element type is `N`, the iterator is an `ExactSizeIterator`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use safe_graph::edge::Direction::Incoming;
+    /// use safe_graph::Graph;
+    ///
+    /// let graph = Graph::<_, ()>::from_edges(&[(1, 2), (2, 2)]);
+    ///
+    /// assert_eq!(graph.neighbors_directed(2, Incoming).collect::<Vec<_>>(), vec![1, 2]);
+    /// ```
+    pub fn neighbors_directed(&self, a: N, dir: Direction) -> NeighborsDirected<N, Ty> {
+        NeighborsDirected::new(self.adjacency(a, dir).iter(), self.ty)
+    }
+
+    /// Return an iterator of target nodes with an edge starting from `a`,
+    /// paired with their respective edge weights.
+    ///
+    /// - `Directed`: Outgoing edges from `a`.
+    /// - `Undirected`: All edges from or to `a`.
+    ///
+    /// Produces an empty iterator if the node doesn't exist.<br>
+    /// Iterator element type is `(N, &E)`.
+    pub fn edges(&self, from: N) -> Edges<N, E, Ty, S> {
+        Edges::new(from, &self.edges, self.neighbors(from))
+    }
+
+    /// Return an iterator of all edges of `n` in the specified direction, paired with their
+    /// respective edge weights.
+    ///
+    /// - `Outgoing`: Edges from `n`, `n` being the source of the yielded edges.
+    /// - `Incoming`: Edges to `n`, `n` being the target of the yielded edges.
+    /// - `Undirected`: All edges from or to `n`, oriented as above.
+    ///
+    /// A self loop is both an `Outgoing` and an `Incoming` edge.
+    /// Produces an empty iterator if the node doesn't exist.<br>
+    /// Iterator element type is `(N, N, &E)`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use safe_graph::edge::Direction::Incoming;
+    /// use safe_graph::Graph;
+    ///
+    /// let graph = Graph::<_, _>::from_edges(&[(1, 3, 0.5), (2, 3, 1.5), (3, 4, 2.5)]);
+    ///
+    /// assert_eq!(
+    ///     graph.edges_directed(3, Incoming).collect::<Vec<_>>(),
+    ///     vec![(1, 3, &0.5), (2, 3, &1.5)]
+    /// );
+    /// ```
+    pub fn edges_directed(&self, n: N, dir: Direction) -> EdgesDirected<'_, N, E, Ty, S> {
+        EdgesDirected::new(n, dir, &self.edges, self.adjacency(n, dir).iter())
+    }
+
+    /// Return a reference to the edge weight connecting `a` with `b`, or
+    /// `None` if the edge does not exist in the graph.
+    pub fn edge_weight(&self, a: N, b: N) -> Option<&E> {
+        self.edges.get(&Self::edge_key(a, b))
+    }
+
+    /// Return a mutable reference to the edge weight connecting `a` with `b`, or
+    /// `None` if the edge does not exist in the graph.
+    pub fn edge_weight_mut(&mut self, a: N, b: N) -> Option<&mut E> {
+        self.edges.get_mut(&Self::edge_key(a, b))
+    }
+
+    /// Return an iterator over all edges of the graph with their weight in arbitrary order.
+    ///
+    /// Iterator element type is `(N, N, &E)`
+    pub fn all_edges(&self) -> AllEdges<N, E, Ty> {
+        AllEdges::new(self.edges.iter(), self.ty)
+    }
+
+    /// Return an iterator over all edges of the graph with their mutable weight, in edge
+    /// insertion order.
+    ///
+    /// Iterator element type is `(N, N, &mut E)`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use safe_graph::Graph;
+    ///
+    /// let mut graph = Graph::<_, _>::from_edges(&[(1, 2, 1.0), (2, 3, 4.0)]);
+    /// for (_, _, weight) in graph.all_edges_mut() {
+    ///     *weight *= 0.5;
+    /// }
+    ///
+    /// assert_eq!(graph.edge_weight(2, 3), Some(&2.0));
+    /// ```
+    pub fn all_edges_mut(&mut self) -> AllEdgesMut<'_, N, E, Ty> {
+        AllEdgesMut::new(self.edges.iter_mut(), self.ty)
+    }
+
+    /// Return an iterator of target nodes with an edge starting from `from`,
+    /// paired with their respective mutable edge weights.
+    ///
+    /// - `Directed`: Outgoing edges from `from`.
+    /// - `Undirected`: All edges from or to `from`.
+    ///
+    /// The edges are listed in the order of `edges`, taking **O(d log d)** time overall for a
+    /// node with `d` neighbors.<br>
+    /// Iterator element type is `(N, N, &mut E)`.
+    pub fn edges_mut(&mut self, from: N) -> EdgesMut<'_, N, E, Ty> {
+        // Edge index and position of every neighbor in the adjacency list.
+        let mut indices: Vec<(usize, usize, N)> = self
+            .adjacency(from, Outgoing)
+            .iter()
+            .enumerate()
+            .map(|(order, &to)| {
+                let index = self.edges.get_index_of(&Self::edge_key(from, to));
+                (index.expect("Edge is not part of the graph."), order, to)
+            })
+            .collect();
+        indices.sort_unstable_by_key(|&(index, _, _)| index);
+
+        // Walk the edges in index order to split them into disjoint mutable weights.
+        let mut iter = self.edges.iter_mut();
+        let mut next = 0;
+        let mut weights = Vec::with_capacity(indices.len());
+        for (index, order, to) in indices {
+            let (_, weight) = iter.nth(index - next).expect("Edge index is out of range.");
+            next = index + 1;
+            weights.push((order, to, weight));
+        }
+        weights.sort_unstable_by_key(|&(order, _, _)| order);
+
+        let weights = weights.into_iter().map(|(_, to, weight)| (to, weight));
+        EdgesMut::new(from, weights.collect(), self.ty)
+    }
+
+    /// Create a new `Graph` by mapping nodes and edges.
+    ///
+    /// `node_map` is called once for every node, `edge_map` once for every edge with its
+    /// original nodes. All nodes, isolated ones included, are kept in their order. Nodes mapped
+    /// to the same value are merged, the last mapped edge weight winning for merged edges.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use safe_graph::Graph;
+    ///
+    /// let mut graph = Graph::<_, _>::from_edges(&[("a", "b", 2), ("b", "c", 3)]);
+    /// graph.add_node("d");
+    ///
+    /// let mapped: Graph<char, f64> = graph.map(
+    ///     |n| n.chars().next().unwrap(),
+    ///     |_, _, &e| f64::from(e) / 2.0,
+    /// );
+    ///
+    /// assert_eq!(mapped.nodes().collect::<Vec<_>>(), vec!['a', 'b', 'c', 'd']);
+    /// assert_eq!(mapped.edge_weight('b', 'c'), Some(&1.5));
+    /// ```
+    pub fn map<N2, E2, F, G>(&self, mut node_map: F, mut edge_map: G) -> Graph<N2, E2, Ty, S>
+    where
+        N2: NodeTrait,
+        F: FnMut(N) -> N2,
+        G: FnMut(N, N, &E) -> E2,
+        S: Clone,
+    {
+        let mut graph = self.empty_like();
+        let nodes: Vec<N2> = self
+            .nodes
+            .keys()
+            .map(|&n| graph.add_node(node_map(n)))
+            .collect();
+
+        for (&(a, b), weight) in &self.edges {
+            let i = self
+                .node_index(a)
+                .expect("Edge node is not part of the graph.");
+            let j = self
+                .node_index(b)
+                .expect("Edge node is not part of the graph.");
+            graph.add_edge(nodes[i], nodes[j], edge_map(a, b, weight));
+        }
+
+        graph
+    }
+
+    /// Create a new `Graph` by mapping and filtering nodes and edges.
+    ///
+    /// Nodes mapped to `None` are removed together with their edges, edges mapped to `None`
+    /// are removed. Otherwise it behaves like `map`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use safe_graph::Graph;
+    ///
+    /// let graph = Graph::<u32, u32>::from_edges(&[(1, 2, 5), (2, 3, 0), (3, 4, 7)]);
+    /// let positive: Graph<u32, u32> = graph.filter_map(
+    ///     |n| if n != 4 { Some(n) } else { None },
+    ///     |_, _, &e| if e > 0 { Some(e) } else { None },
+    /// );
+    ///
+    /// assert_eq!(positive.node_count(), 3);
+    /// assert_eq!(positive.all_edges().collect::<Vec<_>>(), vec![(1, 2, &5)]);
+    /// ```
+    pub fn filter_map<N2, E2, F, G>(&self, mut node_map: F, mut edge_map: G) -> Graph<N2, E2, Ty, S>
+    where
+        N2: NodeTrait,
+        F: FnMut(N) -> Option<N2>,
+        G: FnMut(N, N, &E) -> Option<E2>,
+        S: Clone,
+    {
+        let mut graph = self.empty_like();
+        let nodes: Vec<Option<N2>> = self
+            .nodes
+            .keys()
+            .map(|&n| node_map(n).map(|n2| graph.add_node(n2)))
+            .collect();
+
+        for (&(a, b), weight) in &self.edges {
+            let i = self
+                .node_index(a)
+                .expect("Edge node is not part of the graph.");
+            let j = self
+                .node_index(b)
+                .expect("Edge node is not part of the graph.");
+            if let (Some(a2), Some(b2)) = (nodes[i], nodes[j]) {
+                if let Some(weight) = edge_map(a, b, weight) {
+                    graph.add_edge(a2, b2, weight);
+                }
+            }
+        }
+
+        graph
+    }
+
+    /// Convert the graph by mapping nodes and edges, moving the edge weights into `edge_map`.
+    ///
+    /// It behaves like `map`.
+    pub fn into_map<N2, E2, F, G>(self, mut node_map: F, mut edge_map: G) -> Graph<N2, E2, Ty, S>
+    where
+        N2: NodeTrait,
+        F: FnMut(N) -> N2,
+        G: FnMut(N, N, E) -> E2,
+        S: Clone,
+    {
+        let mut graph = self.empty_like();
+        let nodes: Vec<N2> = self
+            .nodes
+            .keys()
+            .map(|&n| graph.add_node(node_map(n)))
+            .collect();
+
+        let positions = self.edges.keys().map(|&(a, b)| {
+            (
+                self.node_index(a)
+                    .expect("Edge node is not part of the graph."),
+                self.node_index(b)
+                    .expect("Edge node is not part of the graph."),
+            )
+        });
+        let positions: Vec<(usize, usize)> = positions.collect();
+
+        for (((a, b), weight), (i, j)) in self.edges.into_iter().zip(positions) {
+            graph.add_edge(nodes[i], nodes[j], edge_map(a, b, weight));
+        }
+
+        graph
+    }
+
+    /// Create an empty graph with the capacity of this one, sharing its hasher.
+    fn empty_like<N2, E2, Ty2>(&self) -> Graph<N2, E2, Ty2, S>
+    where
+        N2: NodeTrait,
+        Ty2: EdgeType,
+        S: Clone,
+    {
+        Graph::with_capacity_and_hasher(self.nodes.len(), self.edges.len(), self.hasher().clone())
+    }
+}
+
+impl<N, E, Ty, S> Graph<N, E, Ty, S>
+where
+    N: NodeTrait,
+    E: Clone,
+    Ty: EdgeType,
+    S: BuildHasher + Clone,
+{
+    /// Freeze the graph into a `Csr`, a compressed sparse row representation, keeping the
+    /// node order.
+    pub fn to_csr(&self) -> Csr<N, E, Ty> {
+        Csr::from_graph(self)
+    }
+
+    /// Return the subgraph induced by `nodes`, made of these nodes and all edges connecting
+    /// them.
+    ///
+    /// Nodes not contained in the graph are ignored. Nodes and edges keep their order of the
+    /// original graph.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use safe_graph::Graph;
+    ///
+    /// let graph = Graph::<_, _>::from_edges(&[(1, 2, 0.5), (2, 3, 1.5), (3, 1, 2.5)]);
+    /// let sub = graph.induced_subgraph(vec![3, 1]);
+    ///
+    /// assert_eq!(sub.nodes().collect::<Vec<_>>(), vec![1, 3]);
+    /// assert_eq!(sub.all_edges().collect::<Vec<_>>(), vec![(3, 1, &2.5)]);
+    /// ```
+    pub fn induced_subgraph<I>(&self, nodes: I) -> Self
+    where
+        I: IntoIterator<Item = N>,
+    {
+        let included: HashSet<N> = nodes.into_iter().collect();
+
+        let mut graph = Self::with_capacity_and_hasher(included.len(), 0, self.hasher().clone());
+        for n in self.nodes().filter(|n| included.contains(n)) {
+            graph.add_node(n);
+        }
+
+        for (a, b, weight) in self.all_edges() {
+            if included.contains(&a) && included.contains(&b) {
+                graph.add_edge(a, b, weight.clone());
+            }
+        }
+
+        graph
+    }
+
+    /// Return the subgraph induced by the nodes within `radius` hops of `center`.
     ///
-    /// - `Directed`: Outgoing edges from `a`.
-    /// - `Undirected`: All edges from or to `a`.
+    /// Hops follow the edges as `neighbors` does, a radius of `0` returns `center` alone.
+    /// Return an empty graph if `center` doesn't exist.
     ///
-    /// Produces an empty iterator if the node doesn't exist.<br>
-    /// Iterator element type is `N`.
-    pub fn neighbors(&self, a: N) -> Neighbors<N, Ty> {
-        let iter = match self.nodes.get(&a) {
-            Some(neigh) => neigh.iter(),
-            None => [].iter(),
-        };
-
-        Neighbors::new(iter, self.ty)
-    }
-
-    /// Return an iterator of all neighbors that have an edge between them and
-    /// `a`, in the specified direction.
-    /// If the graph's edges are undirected, this is equivalent to *.neighbors(a)*.
+    /// # Examples
     ///
-    /// - `Directed`, `Outgoing`: All edges from `a`.
-    /// - `Directed`, `Incoming`: All edges to `a`.
-    /// - `Undirected`: All edges from or to `a`.
+    /// ```
+    /// use safe_graph::UndirectedGraph;
     ///
-    /// Produces an empty iterator if the node doesn't exist.<br>
-    /// Iterator element type is `N`.
-    pub fn neighbors_directed(&self, a: N, dir: Direction) -> NeighborsDirected<N, Ty> {
-        let iter = match self.nodes.get(&a) {
-            Some(neigh) => neigh.iter(),
-            None => [].iter(),
-        };
+    /// let graph = UndirectedGraph::<_, ()>::from_edges(&[(1, 2), (2, 3), (3, 4), (2, 5)]);
+    /// let ego = graph.ego_graph(2, 1);
+    ///
+    /// assert_eq!(ego.nodes().collect::<Vec<_>>(), vec![1, 2, 3, 5]);
+    /// assert_eq!(ego.edge_count(), 3);
+    /// ```
+    pub fn ego_graph(&self, center: N, radius: usize) -> Self {
+        if !self.contains_node(center) {
+            return Self::with_hasher(self.hasher().clone());
+        }
 
-        NeighborsDirected::new(iter, dir, self.ty)
+        let mut reached = HashSet::new();
+        reached.insert(center);
+        let mut frontier = vec![center];
+
+        for _ in 0..radius {
+            let mut next = Vec::new();
+            for &n in &frontier {
+                for m in self.neighbors(n) {
+                    if reached.insert(m) {
+                        next.push(m);
+                    }
+                }
+            }
+            if next.is_empty() {
+                break;
+            }
+            frontier = next;
+        }
+
+        self.induced_subgraph(reached)
     }
+}
 
-    /// Return an iterator of target nodes with an edge starting from `a`,
-    /// paired with their respective edge weights.
+impl<N, E, S> Graph<N, E, Directed, S>
+where
+    N: NodeTrait,
+    S: BuildHasher + Clone,
+{
+    /// Convert the graph into an undirected graph, keeping all nodes.
     ///
-    /// - `Directed`: Outgoing edges from `a`.
-    /// - `Undirected`: All edges from or to `a`.
+    /// The weights of a pair of reciprocal edges `(a, b)` and `(b, a)` are combined by
+    /// `merge`, called with the weight of the edge with the lower edge index first. That's the
+    /// edge inserted first, unless `remove_edge` has since moved the last edge into the index of
+    /// a removed one.
     ///
-    /// Produces an empty iterator if the node doesn't exist.<br>
-    /// Iterator element type is `(N, &E)`.
-    pub fn edges(&self, from: N) -> Edges<N, E, Ty> {
-        Edges::new(from, &self.edges, self.neighbors(from))
-    }
-
-    /// Return a reference to the edge weight connecting `a` with `b`, or
-    /// `None` if the edge does not exist in the graph.
-    pub fn edge_weight(&self, a: N, b: N) -> Option<&E> {
-        self.edges.get(&Self::edge_key(a, b))
-    }
+    /// # Examples
+    ///
+    /// ```
+    /// use safe_graph::Graph;
+    ///
+    /// let graph = Graph::<_, _>::from_edges(&[(1, 2, 1), (2, 3, 2), (2, 1, 4)]);
+    /// let undirected = graph.into_undirected(|a, b| a + b);
+    ///
+    /// assert_eq!(undirected.edge_count(), 2);
+    /// assert_eq!(undirected.edge_weight(2, 1), Some(&5));
+    /// ```
+    pub fn into_undirected<F>(self, mut merge: F) -> Graph<N, E, Undirected, S>
+    where
+        F: FnMut(E, E) -> E,
+    {
+        let reciprocal: Vec<Option<usize>> = self
+            .edges
+            .keys()
+            .map(|&(a, b)| {
+                if a == b {
+                    None
+                } else {
+                    self.edges.get_index_of(&(b, a))
+                }
+            })
+            .collect();
+
+        let mut graph = self.empty_like();
+        for &n in self.nodes.keys() {
+            graph.add_node(n);
+        }
 
-    /// Return a mutable reference to the edge weight connecting `a` with `b`, or
-    /// `None` if the edge does not exist in the graph.
-    pub fn edge_weight_mut(&mut self, a: N, b: N) -> Option<&mut E> {
-        self.edges.get_mut(&Self::edge_key(a, b))
-    }
+        let mut edges: Vec<Option<((N, N), E)>> = self.edges.into_iter().map(Some).collect();
+        for i in 0..edges.len() {
+            // The second edge of a reciprocal pair has already been merged.
+            if let Some(((a, b), weight)) = edges[i].take() {
+                let weight = match reciprocal[i].and_then(|j| edges[j].take()) {
+                    Some((_, other)) => merge(weight, other),
+                    None => weight,
+                };
+                graph.add_edge(a, b, weight);
+            }
+        }
 
-    /// Return an iterator over all edges of the graph with their weight in arbitrary order.
-    ///
-    /// Iterator element type is `(N, N, &E)`
-    pub fn all_edges(&self) -> AllEdges<N, E, Ty> {
-        AllEdges::new(self.edges.iter(), self.ty)
+        graph
     }
 }
 
 /// Create a new empty `Graph`.
-impl<N, E, Ty> Default for Graph<N, E, Ty>
+impl<N, E, Ty, S> Default for Graph<N, E, Ty, S>
 where
     N: NodeTrait,
     Ty: EdgeType,
+    S: BuildHasher + Default,
 {
     fn default() -> Self {
-        Graph::with_capacity(0, 0)
+        Self::with_capacity_default(0, 0)
     }
 }
 
 /// Create a new `Graph` from an iterable of edges.
-impl<N, E, Ty, Item> FromIterator<Item> for Graph<N, E, Ty>
+impl<N, E, Ty, S, Item> FromIterator<Item> for Graph<N, E, Ty, S>
 where
     Item: IntoWeightedEdge<E, NodeId = N>,
     N: NodeTrait,
     Ty: EdgeType,
+    S: BuildHasher + Default,
 {
     fn from_iter<I>(iterable: I) -> Self
     where
         I: IntoIterator<Item = Item>,
     {
-        let iter = iterable.into_iter();
-        let (low, _) = iter.size_hint();
-        let mut g = Self::with_capacity(0, low);
-        g.extend(iter);
+        let mut g = Self::default();
+        g.extend(iterable);
         g
     }
 }
 
+/// Consume the graph into an iterator of its edges `(N, N, E)`, in insertion order.
+impl<N, E, Ty, S, W> IntoIterator for Graph<N, E, Ty, S, W>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    type Item = (N, N, E);
+    type IntoIter = IntoAllEdges<N, E>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        IntoAllEdges::new(self.edges.into_iter())
+    }
+}
+
+/// Index the graph by a pair of nodes to access the weight of the edge connecting them.
+///
+/// # Panics
+///
+/// Panics if the edge does not exist.
+impl<N, E, Ty, S, W> Index<(N, N)> for Graph<N, E, Ty, S, W>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    type Output = E;
+
+    fn index(&self, (a, b): (N, N)) -> &E {
+        self.edge_weight(a, b).expect("Graph::index: no such edge")
+    }
+}
+
+/// Index the graph by a pair of nodes to access the weight of the edge connecting them.
+///
+/// # Panics
+///
+/// Panics if the edge does not exist.
+impl<N, E, Ty, S, W> IndexMut<(N, N)> for Graph<N, E, Ty, S, W>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    fn index_mut(&mut self, (a, b): (N, N)) -> &mut E {
+        self.edge_weight_mut(a, b)
+            .expect("Graph::index_mut: no such edge")
+    }
+}
+
 /// Extend the graph from an iterable of edges.
 ///
 /// Nodes are inserted automatically to match the edges.
-impl<N, E, Ty, Item> Extend<Item> for Graph<N, E, Ty>
+impl<N, E, Ty, S, W, Item> Extend<Item> for Graph<N, E, Ty, S, W>
 where
     Item: IntoWeightedEdge<E, NodeId = N>,
     N: NodeTrait,
     Ty: EdgeType,
+    S: BuildHasher,
+    W: Default,
 {
     fn extend<I>(&mut self, iterable: I)
     where
@@ -337,6 +1240,7 @@ where
         let iter = iterable.into_iter();
         let (low, _) = iter.size_hint();
         self.edges.reserve(low);
+        self.positions.reserve(low);
 
         for elt in iter {
             let (source, target, weight) = elt.into_weighted_edge();
@@ -347,8 +1251,15 @@ where
 
 #[cfg(test)]
 mod tests {
-    use crate::edge::Direction::{Incoming, Outgoing};
-    use crate::graph::{Directed, Graph, Undirected};
+    use crate::edge::{
+        Direction::{Incoming, Outgoing},
+        EdgeType,
+    };
+    use crate::graph::{Directed, Graph, Undirected, UndirectedGraph};
+    use rand_core::{RngCore, SeedableRng};
+    use rand_pcg::Pcg32;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::BuildHasherDefault;
 
     #[test]
     fn new() {
@@ -377,6 +1288,29 @@ mod tests {
         assert_eq!(graph.edge_count(), 0);
     }
 
+    #[test]
+    fn with_hasher() {
+        type Hasher = BuildHasherDefault<DefaultHasher>;
+
+        let mut graph: Graph<u32, f32, Undirected, Hasher> =
+            Graph::with_capacity_and_hasher(3, 2, Hasher::default());
+        graph.add_edge(1, 2, 1.0);
+        graph.add_edge(3, 2, 2.0);
+
+        assert_eq!(graph.edge_weight(2, 3), Some(&2.0));
+        assert_eq!(graph.neighbors(2).collect::<Vec<_>>(), vec![1, 3]);
+
+        // Test derived graphs keep the hasher type.
+        let doubled: Graph<u32, f32, Undirected, Hasher> = graph.map(|n| n, |_, _, &w| 2.0 * w);
+        assert_eq!(doubled[(3, 2)], 4.0);
+        assert_eq!(graph.induced_subgraph(vec![1, 2]).edge_count(), 1);
+
+        let collected: Graph<u32, (), Directed, Hasher> =
+            vec![(1, 2), (2, 1)].into_iter().collect();
+        let undirected = collected.into_undirected(|a, _| a);
+        assert_eq!(undirected.edge_count(), 1);
+    }
+
     #[test]
     fn capacity() {
         let nodes_capacity = 4;
@@ -773,4 +1707,436 @@ mod tests {
         let mut neighbors_4 = graph.neighbors_directed(4, Incoming);
         assert_eq!(neighbors_4.next(), None);
     }
+
+    #[test]
+    fn neighbors_directed_self_loop() {
+        let mut graph = Graph::<u32, ()>::from_edges(&[(1, 1), (2, 1)]);
+
+        // Test a directed self loop is an incoming edge of its node.
+        assert_eq!(
+            graph.neighbors_directed(1, Incoming).collect::<Vec<_>>(),
+            vec![1, 2]
+        );
+        assert_eq!(
+            graph.neighbors_directed(1, Outgoing).collect::<Vec<_>>(),
+            vec![1]
+        );
+        assert_eq!(
+            graph.edges_directed(1, Incoming).collect::<Vec<_>>(),
+            vec![(1, 1, &()), (2, 1, &())]
+        );
+
+        // Test removing the self loop removes both entries.
+        graph.remove_edge(1, 1);
+
+        assert_eq!(
+            graph.neighbors_directed(1, Incoming).collect::<Vec<_>>(),
+            vec![2]
+        );
+        assert_eq!(graph.neighbors_directed(1, Outgoing).count(), 0);
+    }
+
+    #[test]
+    fn neighbors_exact_size() {
+        let graph = Graph::<u32, ()>::from_edges(&[(1, 2), (3, 1), (1, 1), (1, 4)]);
+
+        // Test a self loop is listed in both directions.
+        assert_eq!(graph.neighbors(1).len(), 3);
+        assert_eq!(
+            graph.neighbors_directed(1, Incoming).collect::<Vec<_>>(),
+            vec![3, 1]
+        );
+        assert_eq!(graph.edges(1).len(), 3);
+        assert_eq!(graph.edges_directed(1, Incoming).len(), 2);
+        assert_eq!(graph.neighbors_directed(5, Outgoing).len(), 0);
+
+        let graph = UndirectedGraph::<u32, ()>::from_edges(&[(1, 2), (3, 1), (1, 1)]);
+
+        // Test an undirected self loop is listed once.
+        assert_eq!(graph.neighbors(1).collect::<Vec<_>>(), vec![2, 3, 1]);
+        assert_eq!(graph.neighbors_directed(1, Incoming).len(), 3);
+    }
+
+    #[test]
+    fn induced_subgraph() {
+        let graph = Graph::<u32, f32>::from_edges(&[
+            (1, 2, 1.0),
+            (2, 3, 2.0),
+            (3, 1, 3.0),
+            (3, 4, 4.0),
+            (4, 4, 5.0),
+        ]);
+
+        let sub = graph.induced_subgraph(vec![4, 3, 1, 7]);
+
+        // Test the order of nodes and edges is preserved.
+        assert_eq!(sub.nodes().collect::<Vec<_>>(), vec![1, 3, 4]);
+        assert_eq!(
+            sub.all_edges().collect::<Vec<_>>(),
+            vec![(3, 1, &3.0), (3, 4, &4.0), (4, 4, &5.0)]
+        );
+        assert_eq!(sub.neighbors(3).collect::<Vec<_>>(), vec![1, 4]);
+        assert_eq!(
+            sub.neighbors_directed(1, Incoming).collect::<Vec<_>>(),
+            vec![3]
+        );
+        assert_eq!(sub.neighbors(1).next(), None);
+
+        assert_eq!(graph.induced_subgraph(vec![]).node_count(), 0);
+    }
+
+    #[test]
+    fn ego_graph() {
+        let graph = Graph::<u32, ()>::from_edges(&[(1, 2), (2, 3), (3, 4), (0, 1), (2, 5)]);
+
+        let ego = graph.ego_graph(1, 2);
+        assert_eq!(ego.nodes().collect::<Vec<_>>(), vec![1, 2, 3, 5]);
+        assert_eq!(ego.edge_count(), 3);
+
+        let ego = graph.ego_graph(1, 0);
+        assert_eq!(ego.nodes().collect::<Vec<_>>(), vec![1]);
+        assert_eq!(ego.edge_count(), 0);
+
+        // Test undirected edges are followed both ways.
+        let undirected = Graph::<u32, (), Undirected>::from_edges(&[(1, 2), (2, 3), (0, 1)]);
+        let ego = undirected.ego_graph(2, 1);
+        assert_eq!(ego.nodes().collect::<Vec<_>>(), vec![1, 2, 3]);
+
+        assert_eq!(graph.ego_graph(9, 3).node_count(), 0);
+    }
+
+    #[test]
+    fn into_undirected() {
+        let mut graph =
+            Graph::<u32, u32>::from_edges(&[(1, 2, 1), (3, 1, 2), (2, 1, 4), (2, 2, 8)]);
+        graph.add_node(4);
+
+        let undirected = graph.into_undirected(|a, b| a * 10 + b);
+
+        assert_eq!(undirected.nodes().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!(
+            undirected.all_edges().collect::<Vec<_>>(),
+            vec![(1, 2, &14), (1, 3, &2), (2, 2, &8)]
+        );
+        assert_eq!(undirected.neighbors(1).collect::<Vec<_>>(), vec![2, 3]);
+    }
+
+    #[test]
+    fn map() {
+        let mut graph = Graph::<u32, f32, Undirected>::from_edges(&[(1, 2, 0.5), (3, 2, 1.5)]);
+        graph.add_node(4);
+
+        // Test the edge canonical order follows the mapped nodes.
+        let mapped: Graph<i64, String, Undirected> =
+            graph.map(|n| -(n as i64), |a, b, e| format!("{}-{}:{}", a, b, e));
+
+        assert_eq!(mapped.nodes().collect::<Vec<_>>(), vec![-1, -2, -3, -4]);
+        assert_eq!(mapped.edge_weight(-1, -2), Some(&"1-2:0.5".to_string()));
+        assert_eq!(mapped.edge_weight(-3, -2), Some(&"2-3:1.5".to_string()));
+        assert_eq!(mapped.neighbors(-2).collect::<Vec<_>>(), vec![-1, -3]);
+    }
+
+    #[test]
+    fn filter_map() {
+        let mut graph = Graph::<u32, u32>::from_edges(&[(1, 2, 1), (2, 3, 2), (3, 1, 3)]);
+        graph.add_node(4);
+
+        let filtered: Graph<u32, u32> = graph.filter_map(
+            |n| if n != 2 { Some(n * 10) } else { None },
+            |_, _, &e| Some(e * 2),
+        );
+
+        assert_eq!(filtered.nodes().collect::<Vec<_>>(), vec![10, 30, 40]);
+        assert_eq!(filtered.all_edges().collect::<Vec<_>>(), vec![(30, 10, &6)]);
+    }
+
+    #[test]
+    fn into_map() {
+        let mut graph = Graph::<&str, String>::new();
+        graph.add_edge("a", "b", "ab".to_string());
+        graph.add_node("c");
+
+        let mapped: Graph<usize, usize> =
+            graph.into_map(|n| n.as_bytes()[0] as usize, |_, _, e| e.len());
+
+        assert_eq!(mapped.nodes().collect::<Vec<_>>(), vec![97, 98, 99]);
+        assert_eq!(mapped.all_edges().collect::<Vec<_>>(), vec![(97, 98, &2)]);
+    }
+
+    #[test]
+    fn nodes_edges() {
+        let mut graph = Graph::<u32, String, Undirected>::new();
+        graph.add_node(5);
+        graph.add_edge(2, 1, "a".to_string());
+        graph.add_edge(1, 3, "b".to_string());
+        graph.add_node(4);
+
+        let (nodes, edges) = graph.clone().into_nodes_edges();
+
+        assert_eq!(nodes, vec![5, 2, 1, 3, 4]);
+        assert_eq!(
+            edges,
+            vec![(1, 2, "a".to_string()), (1, 3, "b".to_string())]
+        );
+
+        // Test the graph is rebuilt with the same order.
+        let rebuilt: Graph<u32, String, Undirected> = Graph::from_nodes_edges(nodes, edges);
+
+        assert_eq!(rebuilt.nodes().collect::<Vec<_>>(), vec![5, 2, 1, 3, 4]);
+        assert_eq!(
+            rebuilt.all_edges().collect::<Vec<_>>(),
+            graph.all_edges().collect::<Vec<_>>()
+        );
+
+        // Test nodes missing from the list are appended.
+        let graph: Graph<u32, ()> = Graph::from_nodes_edges(vec![2], vec![(1, 2)]);
+        assert_eq!(graph.nodes().collect::<Vec<_>>(), vec![2, 1]);
+    }
+
+    #[test]
+    fn into_iter() {
+        let graph = Graph::<u32, Vec<u32>>::from_edges(vec![(1, 2, vec![1]), (2, 3, vec![2])]);
+        let mut weights = Vec::new();
+
+        for (_, _, weight) in graph {
+            weights.extend(weight);
+        }
+
+        assert_eq!(weights, vec![1, 2]);
+    }
+
+    #[test]
+    fn all_edges_mut() {
+        let mut graph = Graph::<u32, f32>::from_edges(&[(1, 2, 1.0), (2, 3, 2.0), (3, 1, 4.0)]);
+
+        for (a, _, weight) in graph.all_edges_mut() {
+            *weight += a as f32;
+        }
+
+        assert_eq!(
+            graph.all_edges().collect::<Vec<_>>(),
+            vec![(1, 2, &2.0), (2, 3, &4.0), (3, 1, &7.0)]
+        );
+    }
+
+    #[test]
+    fn edges_mut() {
+        let mut graph = Graph::<u32, f32>::from_edges(&[(1, 2, 1.0), (2, 3, 2.0), (1, 3, 4.0)]);
+
+        for (_, _, weight) in graph.edges_mut(1) {
+            *weight *= 10.0;
+        }
+
+        assert_eq!(
+            graph.all_edges().collect::<Vec<_>>(),
+            vec![(1, 2, &10.0), (2, 3, &2.0), (1, 3, &40.0)]
+        );
+        assert_eq!(graph.edges_mut(4).next(), None);
+
+        let mut graph = Graph::<u32, f32, Undirected>::from_edges(&[(1, 2, 1.0), (2, 3, 2.0)]);
+        let edges: Vec<_> = graph.edges_mut(2).map(|(a, b, &mut w)| (a, b, w)).collect();
+
+        assert_eq!(edges, vec![(2, 1, 1.0), (2, 3, 2.0)]);
+
+        // Test the order follows `edges` rather than the order of edge indices.
+        let mut graph =
+            Graph::<u32, f32, Undirected>::from_edges(&[(2, 3, 1.0), (1, 2, 2.0), (1, 3, 3.0)]);
+        graph.remove_edge(2, 3);
+        let expected: Vec<_> = graph.edges(1).map(|(a, b, &w)| (a, b, w)).collect();
+        let edges: Vec<_> = graph.edges_mut(1).map(|(a, b, &mut w)| (a, b, w)).collect();
+
+        assert_eq!(edges, vec![(1, 2, 2.0), (1, 3, 3.0)]);
+        assert_eq!(edges, expected);
+        assert_eq!(graph.edges_mut(1).len(), 2);
+    }
+
+    #[test]
+    fn edges_directed() {
+        let graph =
+            Graph::<u32, f32>::from_edges(&[(1, 2, 1.0), (3, 1, 2.0), (2, 1, 3.0), (1, 1, 4.0)]);
+
+        assert_eq!(
+            graph.edges_directed(1, Outgoing).collect::<Vec<_>>(),
+            vec![(1, 2, &1.0), (1, 1, &4.0)]
+        );
+        assert_eq!(
+            graph.edges_directed(1, Incoming).collect::<Vec<_>>(),
+            vec![(3, 1, &2.0), (2, 1, &3.0), (1, 1, &4.0)]
+        );
+        assert_eq!(graph.edges_directed(5, Incoming).next(), None);
+
+        // Test undirected edges are oriented by the direction.
+        let graph = Graph::<u32, f32, Undirected>::from_edges(&[(1, 2, 1.0), (3, 1, 2.0)]);
+
+        assert_eq!(
+            graph.edges_directed(1, Outgoing).collect::<Vec<_>>(),
+            vec![(1, 2, &1.0), (1, 3, &2.0)]
+        );
+        assert_eq!(
+            graph.edges_directed(1, Incoming).collect::<Vec<_>>(),
+            vec![(2, 1, &1.0), (3, 1, &2.0)]
+        );
+    }
+
+    #[test]
+    fn remove_edge() {
+        let mut graph =
+            Graph::<u32, f32>::from_edges(&[(1, 2, 1.0), (3, 1, 2.0), (1, 1, 3.0), (1, 4, 4.0)]);
+
+        assert_eq!(graph.remove_edge(2, 1), None);
+        assert_eq!(graph.remove_edge(1, 2), Some(1.0));
+        assert_eq!(graph.remove_edge(1, 2), None);
+
+        // Test the last edge and the last neighbors are moved in place of the removed ones.
+        assert_eq!(graph.edge_index(1, 4), Some(0));
+        assert_eq!(graph.neighbors(1).collect::<Vec<_>>(), vec![4, 1]);
+        assert_eq!(
+            graph.neighbors_directed(1, Incoming).collect::<Vec<_>>(),
+            vec![3, 1]
+        );
+        assert_eq!(graph.neighbors_directed(2, Incoming).next(), None);
+
+        assert_eq!(graph.remove_edge(1, 1), Some(3.0));
+        assert_eq!(graph.degree(1), 2);
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_count(), 2);
+
+        // Test undirected edges are removed from both nodes.
+        let mut graph =
+            Graph::<u32, f32, Undirected>::from_edges(&[(1, 2, 1.0), (3, 1, 2.0), (3, 3, 3.0)]);
+
+        assert_eq!(graph.remove_edge(1, 3), Some(2.0));
+        assert_eq!(graph.neighbors(1).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(graph.neighbors(3).collect::<Vec<_>>(), vec![3]);
+        assert_eq!(graph.remove_edge(3, 3), Some(3.0));
+        assert_eq!(graph.neighbors(3).next(), None);
+    }
+
+    fn assert_positions<Ty: EdgeType>(graph: &Graph<u32, (), Ty>) {
+        assert_eq!(graph.positions.len(), graph.edge_count());
+
+        for (&(a, b), &[p, q]) in graph.edges.keys().zip(&graph.positions) {
+            let second = if Ty::is_directed() {
+                Incoming
+            } else {
+                Outgoing
+            };
+            assert_eq!(graph.nodes[&a].get(Outgoing)[p], b);
+            assert_eq!(graph.nodes[&b].get(second)[q], a);
+        }
+
+        let listed: usize = graph
+            .nodes()
+            .map(|n| graph.neighbors(n).len() + graph.neighbors_directed(n, Incoming).len())
+            .sum();
+        let self_loops = graph.all_edges().filter(|&(a, b, _)| a == b).count();
+        if Ty::is_directed() {
+            assert_eq!(listed, 2 * graph.edge_count());
+        } else {
+            assert_eq!(listed, 2 * (2 * graph.edge_count() - self_loops));
+        }
+    }
+
+    fn remove_edge_churn<Ty: EdgeType>() {
+        let mut rng = Pcg32::seed_from_u64(7);
+        let mut graph: Graph<u32, (), Ty> = Graph::new();
+
+        for _ in 0..2000 {
+            let (a, b) = (rng.next_u32() % 12, rng.next_u32() % 12);
+            if rng.next_u32() % 3 == 0 {
+                let existed = graph.contains_edge(a, b);
+                assert_eq!(graph.remove_edge(a, b).is_some(), existed);
+                assert!(!graph.contains_edge(a, b));
+            } else {
+                graph.add_edge(a, b, ());
+            }
+            assert_positions(&graph);
+        }
+    }
+
+    #[test]
+    fn remove_edge_positions() {
+        remove_edge_churn::<Directed>();
+        remove_edge_churn::<Undirected>();
+    }
+
+    #[test]
+    fn degree() {
+        let mut graph = Graph::<u32, ()>::from_edges(&[(1, 2), (3, 1), (1, 1), (2, 3)]);
+        graph.add_node(4);
+
+        // Test the self loop is counted both as incoming and outgoing edge.
+        assert_eq!(graph.degree(1), 4);
+        assert_eq!(graph.in_degree(1), 2);
+        assert_eq!(graph.out_degree(1), 2);
+        assert_eq!(graph.in_degree(2), 1);
+        assert_eq!(graph.out_degree(2), 1);
+        assert_eq!(graph.degree(4), 0);
+        assert_eq!(graph.degree(5), 0);
+
+        let graph = Graph::<u32, (), Undirected>::from_edges(&[(1, 2), (1, 1)]);
+
+        assert_eq!(graph.degree(1), 3);
+        assert_eq!(graph.in_degree(1), 3);
+        assert_eq!(graph.out_degree(2), 1);
+    }
+
+    #[test]
+    fn degree_statistics() {
+        let mut graph = Graph::<u32, ()>::from_edges(&[(1, 2), (1, 3), (2, 3), (3, 3)]);
+        graph.add_node(4);
+
+        assert_eq!(graph.degree_histogram(), vec![1, 0, 2, 0, 1]);
+        assert_eq!(graph.max_degree(), 4);
+        assert_eq!(graph.density(), 4.0 / 12.0);
+        assert_eq!(graph.sources().collect::<Vec<_>>(), vec![1, 4]);
+        assert_eq!(graph.sinks().collect::<Vec<_>>(), vec![4]);
+
+        let graph = Graph::<u32, (), Undirected>::from_edges(&[(1, 2), (2, 3), (3, 1)]);
+        assert_eq!(graph.density(), 1.0);
+        assert_eq!(graph.sources().next(), None);
+
+        let graph: Graph<u32, ()> = Graph::new();
+        assert_eq!(graph.degree_histogram(), vec![0]);
+        assert_eq!(graph.max_degree(), 0);
+        assert_eq!(graph.density(), 0.0);
+    }
+
+    #[test]
+    fn node_and_edge_index() {
+        let graph = Graph::<u32, f32, Undirected>::from_edges(&[(2, 1, 1.0), (1, 3, 2.0)]);
+
+        assert_eq!(graph.node_index(2), Some(0));
+        assert_eq!(graph.node_index(3), Some(2));
+        assert_eq!(graph.node_index(4), None);
+        assert_eq!(graph.node_at(1), Some(1));
+        assert_eq!(graph.node_at(3), None);
+
+        assert_eq!(graph.edge_index(2, 1), Some(0));
+        assert_eq!(graph.edge_index(3, 1), Some(1));
+        assert_eq!(graph.edge_index(2, 3), None);
+        assert_eq!(graph.edge_at(0), Some((1, 2, &1.0)));
+        assert_eq!(graph.edge_at(2), None);
+
+        // Test a side table indexed by node index.
+        let degrees: Vec<usize> = graph.nodes().map(|n| graph.degree(n)).collect();
+        assert_eq!(degrees[graph.node_index(1).unwrap()], 2);
+    }
+
+    #[test]
+    fn index_edge_weight() {
+        let mut graph = Graph::<u32, f32>::from_edges(&[(1, 2, 1.0)]);
+
+        graph[(1, 2)] += 2.0;
+
+        assert_eq!(graph[(1, 2)], 3.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_missing_edge() {
+        let graph = Graph::<u32, f32>::from_edges(&[(1, 2, 1.0)]);
+
+        let _ = graph[(2, 1)];
+    }
 }
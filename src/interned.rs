@@ -0,0 +1,386 @@
+//! Graph with arbitrary node keys, interned to compact node ids.
+
+use crate::edge::{AllEdges, EdgeType, Edges};
+use crate::graph::{Directed, Graph};
+use crate::node::Nodes;
+use crate::traverse::Neighbors;
+use indexmap::IndexSet;
+use std::borrow::Borrow;
+use std::fmt;
+use std::hash::Hash;
+
+/// `InternedGraph<K, E, Ty>` is a graph whose nodes are keys `K` without the `Copy` and `Ord`
+/// requirements of `NodeTrait`, like `String` or `PathBuf`.
+///
+/// Every key is interned once to a `usize` node id, its position in key insertion order, and
+/// the graph itself is a `Graph<usize, E, Ty>` of these ids. Keys are looked up by any
+/// borrowed form `&Q` and the iterators resolve ids back to keys.
+///
+/// # Examples
+///
+/// ```
+/// use safe_graph::InternedGraph;
+///
+/// let mut graph: InternedGraph<String, u32> = InternedGraph::new();
+/// graph.add_edge("src/lib.rs".to_string(), "src/graph.rs".to_string(), 1);
+///
+/// assert!(graph.contains_edge("src/lib.rs", "src/graph.rs"));
+/// assert_eq!(
+///     graph.neighbors("src/lib.rs").collect::<Vec<_>>(),
+///     vec!["src/graph.rs"]
+/// );
+/// assert_eq!(graph.node_id("src/graph.rs"), Some(1));
+/// ```
+#[derive(Clone)]
+pub struct InternedGraph<K, E, Ty = Directed> {
+    graph: Graph<usize, E, Ty>,
+    keys: IndexSet<K>,
+}
+
+impl<K, E, Ty> fmt::Debug for InternedGraph<K, E, Ty>
+where
+    K: fmt::Debug,
+    E: fmt::Debug,
+    Ty: EdgeType,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InternedGraph")
+            .field("keys", &self.keys)
+            .field("graph", &self.graph)
+            .finish()
+    }
+}
+
+impl<K, E, Ty> InternedGraph<K, E, Ty>
+where
+    K: Hash + Eq,
+    Ty: EdgeType,
+{
+    /// Create a new `InternedGraph` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new `InternedGraph` with estimated capacity.
+    pub fn with_capacity(nodes: usize, edges: usize) -> Self {
+        Self {
+            graph: Graph::with_capacity(nodes, edges),
+            keys: IndexSet::with_capacity(nodes),
+        }
+    }
+
+    /// Create a new `InternedGraph` from an iterable of edges.
+    pub fn from_edges<I>(iterable: I) -> Self
+    where
+        I: IntoIterator<Item = (K, K, E)>,
+    {
+        let mut graph = Self::new();
+        graph.extend(iterable);
+        graph
+    }
+
+    /// Return the number of nodes in the graph.
+    pub fn node_count(&self) -> usize {
+        self.graph.node_count()
+    }
+
+    /// Return the number of edges in the graph.
+    pub fn edge_count(&self) -> usize {
+        self.graph.edge_count()
+    }
+
+    /// Remove all nodes and edges, node ids are reassigned from `0`.
+    pub fn clear(&mut self) {
+        self.graph.clear();
+        self.keys.clear();
+    }
+
+    /// Add node `key` to the graph and return its id, the existing id if the key was already
+    /// interned.
+    pub fn add_node(&mut self, key: K) -> usize {
+        let (id, _) = self.keys.insert_full(key);
+        self.graph.add_node(id)
+    }
+
+    /// Add an edge connecting `a` and `b` to the graph, with associated data `weight`, like
+    /// `Graph::add_edge`.
+    pub fn add_edge(&mut self, a: K, b: K, weight: E) -> Option<E> {
+        let a = self.add_node(a);
+        let b = self.add_node(b);
+        self.graph.add_edge(a, b, weight)
+    }
+
+    /// Return the id of node `key`, or `None` if the node does not exist in the graph.
+    pub fn node_id<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.keys.get_index_of(key)
+    }
+
+    /// Return the key of node `id`, or `None` if the id is not assigned.
+    pub fn node_key(&self, id: usize) -> Option<&K> {
+        self.keys.get_index(id)
+    }
+
+    /// Return `true` if the node is contained in the graph.
+    pub fn contains_node<Q>(&self, key: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.keys.contains(key)
+    }
+
+    /// Return `true` if the edge connecting `a` with `b` is contained in the graph.
+    pub fn contains_edge<Q>(&self, a: &Q, b: &Q) -> bool
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.edge_ids(a, b)
+            .is_some_and(|(a, b)| self.graph.contains_edge(a, b))
+    }
+
+    /// Return a reference to the edge weight connecting `a` with `b`, or
+    /// `None` if the edge does not exist in the graph.
+    pub fn edge_weight<Q>(&self, a: &Q, b: &Q) -> Option<&E>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (a, b) = self.edge_ids(a, b)?;
+        self.graph.edge_weight(a, b)
+    }
+
+    /// Return a mutable reference to the edge weight connecting `a` with `b`, or
+    /// `None` if the edge does not exist in the graph.
+    pub fn edge_weight_mut<Q>(&mut self, a: &Q, b: &Q) -> Option<&mut E>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let (a, b) = self.edge_ids(a, b)?;
+        self.graph.edge_weight_mut(a, b)
+    }
+
+    /// Return an iterator over the node keys of the graph, in insertion order.
+    ///
+    /// Iterator element type is `&K`.
+    pub fn nodes(&self) -> Resolve<'_, K, Nodes<'_, usize>> {
+        Resolve::new(&self.keys, Some(self.graph.nodes()))
+    }
+
+    /// Return an iterator of all nodes with an edge starting from `a`, like `Graph::neighbors`.
+    ///
+    /// Produces an empty iterator if the node doesn't exist.<br>
+    /// Iterator element type is `&K`.
+    pub fn neighbors<Q>(&self, a: &Q) -> Resolve<'_, K, Neighbors<'_, usize, Ty>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let iter = self.node_id(a).map(|a| self.graph.neighbors(a));
+        Resolve::new(&self.keys, iter)
+    }
+
+    /// Return an iterator of the edges starting from `a` with their weight, like
+    /// `Graph::edges`.
+    ///
+    /// Produces an empty iterator if the node doesn't exist.<br>
+    /// Iterator element type is `(&K, &K, &E)`.
+    pub fn edges<Q>(&self, a: &Q) -> ResolveEdges<'_, K, Edges<'_, usize, E, Ty>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let iter = self.node_id(a).map(|a| self.graph.edges(a));
+        ResolveEdges::new(&self.keys, iter)
+    }
+
+    /// Return an iterator over all edges of the graph with their weight, in insertion order.
+    ///
+    /// Iterator element type is `(&K, &K, &E)`.
+    pub fn all_edges(&self) -> ResolveEdges<'_, K, AllEdges<'_, usize, E, Ty>> {
+        ResolveEdges::new(&self.keys, Some(self.graph.all_edges()))
+    }
+
+    /// Return the graph of node ids, to run algorithms on.
+    pub fn graph(&self) -> &Graph<usize, E, Ty> {
+        &self.graph
+    }
+
+    /// Return the ids of the nodes of an edge, if both exist.
+    fn edge_ids<Q>(&self, a: &Q, b: &Q) -> Option<(usize, usize)>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        Some((self.node_id(a)?, self.node_id(b)?))
+    }
+}
+
+/// Create a new empty `InternedGraph`.
+impl<K, E, Ty> Default for InternedGraph<K, E, Ty>
+where
+    K: Hash + Eq,
+    Ty: EdgeType,
+{
+    fn default() -> Self {
+        InternedGraph::with_capacity(0, 0)
+    }
+}
+
+/// Extend the graph from an iterable of edges.
+///
+/// Nodes are inserted automatically to match the edges.
+impl<K, E, Ty> Extend<(K, K, E)> for InternedGraph<K, E, Ty>
+where
+    K: Hash + Eq,
+    Ty: EdgeType,
+{
+    fn extend<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = (K, K, E)>,
+    {
+        for (a, b, weight) in iterable {
+            self.add_edge(a, b, weight);
+        }
+    }
+}
+
+/// Iterator resolving node ids to their keys, empty for a missing node.
+///
+/// Iterator element type is `&K`.
+pub struct Resolve<'a, K, I> {
+    keys: &'a IndexSet<K>,
+    iter: Option<I>,
+}
+
+impl<'a, K, I> Resolve<'a, K, I> {
+    fn new(keys: &'a IndexSet<K>, iter: Option<I>) -> Self {
+        Self { keys, iter }
+    }
+}
+
+impl<'a, K, I> Iterator for Resolve<'a, K, I>
+where
+    I: Iterator<Item = usize>,
+{
+    type Item = &'a K;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = self.iter.as_mut()?.next()?;
+        self.keys.get_index(id)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter
+            .as_ref()
+            .map_or((0, Some(0)), |iter| iter.size_hint())
+    }
+}
+
+/// Iterator resolving the node ids of edges to their keys, empty for a missing node.
+///
+/// Iterator element type is `(&K, &K, &E)`.
+pub struct ResolveEdges<'a, K, I> {
+    keys: &'a IndexSet<K>,
+    iter: Option<I>,
+}
+
+impl<'a, K, I> ResolveEdges<'a, K, I> {
+    fn new(keys: &'a IndexSet<K>, iter: Option<I>) -> Self {
+        Self { keys, iter }
+    }
+}
+
+impl<'a, K, E, I> Iterator for ResolveEdges<'a, K, I>
+where
+    I: Iterator<Item = (usize, usize, &'a E)>,
+    E: 'a,
+{
+    type Item = (&'a K, &'a K, &'a E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (a, b, weight) = self.iter.as_mut()?.next()?;
+        Some((self.keys.get_index(a)?, self.keys.get_index(b)?, weight))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter
+            .as_ref()
+            .map_or((0, Some(0)), |iter| iter.size_hint())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::Undirected;
+    use crate::interned::InternedGraph;
+    use std::path::{Path, PathBuf};
+
+    #[test]
+    fn add_node() {
+        let mut graph: InternedGraph<String, ()> = InternedGraph::new();
+
+        assert_eq!(graph.add_node("a".to_string()), 0);
+        assert_eq!(graph.add_node("b".to_string()), 1);
+        assert_eq!(graph.add_node("a".to_string()), 0);
+
+        assert_eq!(graph.node_count(), 2);
+        assert!(graph.contains_node("b"));
+        assert!(!graph.contains_node("c"));
+        assert_eq!(graph.node_id("b"), Some(1));
+        assert_eq!(graph.node_key(1).map(String::as_str), Some("b"));
+        assert_eq!(graph.node_key(2), None);
+    }
+
+    #[test]
+    fn edges() {
+        let mut graph: InternedGraph<PathBuf, u32> = InternedGraph::from_edges(vec![
+            (PathBuf::from("a"), PathBuf::from("b"), 1),
+            (PathBuf::from("a"), PathBuf::from("c"), 2),
+        ]);
+
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(graph.edge_weight(Path::new("a"), Path::new("c")), Some(&2));
+        assert_eq!(graph.edge_weight(Path::new("c"), Path::new("a")), None);
+        assert_eq!(graph.edge_weight(Path::new("a"), Path::new("d")), None);
+
+        *graph
+            .edge_weight_mut(Path::new("a"), Path::new("b"))
+            .unwrap() = 5;
+
+        assert_eq!(
+            graph.edges(Path::new("a")).collect::<Vec<_>>(),
+            vec![
+                (&PathBuf::from("a"), &PathBuf::from("b"), &5),
+                (&PathBuf::from("a"), &PathBuf::from("c"), &2)
+            ]
+        );
+        assert_eq!(graph.edges(Path::new("d")).next(), None);
+    }
+
+    #[test]
+    fn iterators() {
+        let graph = InternedGraph::<String, f32, Undirected>::from_edges(vec![
+            ("x".to_string(), "y".to_string(), 0.5),
+            ("z".to_string(), "x".to_string(), 1.5),
+        ]);
+
+        assert_eq!(graph.nodes().collect::<Vec<_>>(), vec!["x", "y", "z"]);
+        assert_eq!(graph.neighbors("x").collect::<Vec<_>>(), vec!["y", "z"]);
+        assert_eq!(graph.neighbors("w").next(), None);
+        assert_eq!(
+            graph.all_edges().collect::<Vec<_>>(),
+            vec![
+                (&"x".to_string(), &"y".to_string(), &0.5),
+                (&"x".to_string(), &"z".to_string(), &1.5)
+            ]
+        );
+        assert!(graph.contains_edge("x", "z"));
+        assert_eq!(graph.graph().edge_weight(2, 0), Some(&1.5));
+    }
+}
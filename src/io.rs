@@ -0,0 +1,269 @@
+//! Reading, writing and exporting of `Graph`s in various file formats.
+//!
+//! Readers stream from any `BufRead` source, so even very large datasets can be loaded
+//! without being materialised in memory first.
+
+pub mod adjacency_list;
+pub mod binary;
+pub mod diagram;
+pub mod dimacs;
+pub mod edge_list;
+pub mod matrix_market;
+
+use crate::edge::EdgeType;
+use crate::graph::Graph;
+use crate::node::NodeTrait;
+use std::error;
+use std::fmt;
+use std::hash::BuildHasher;
+use std::io::{self, BufRead};
+use std::str::{FromStr, Split, SplitWhitespace};
+
+/// Error produced while reading or writing a graph.
+#[derive(Debug)]
+pub enum Error {
+    /// Error of the underlying reader or writer.
+    Io(io::Error),
+    /// Malformed input, with the 1-based number of the offending line.
+    Parse { line: usize, message: String },
+    /// Malformed or corrupted binary input, or a graph the format can't represent.
+    Format(String),
+}
+
+impl Error {
+    /// Create a new `Error::Parse` for the given line.
+    pub fn parse<M: Into<String>>(line: usize, message: M) -> Self {
+        Error::Parse {
+            line,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "I/O error: {}", err),
+            Error::Parse { line, message } => write!(f, "line {}: {}", line, message),
+            Error::Format(message) => write!(f, "invalid data: {}", message),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            Error::Parse { .. } | Error::Format(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::Io(err)
+    }
+}
+
+/// Separator of the fields on a line of a text format.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Delimiter {
+    /// Any run of whitespace, e.g. `1 2` or `1\t2`.
+    Whitespace,
+    /// A single character, e.g. `,` for CSV.
+    Char(char),
+}
+
+impl Delimiter {
+    /// Split a line into its trimmed fields.
+    pub(crate) fn split(self, line: &str) -> Fields<'_> {
+        match self {
+            Delimiter::Whitespace => Fields::Whitespace(line.split_whitespace()),
+            Delimiter::Char(c) => Fields::Char(line.split(c)),
+        }
+    }
+}
+
+/// Iterator over the trimmed fields of a line split by a `Delimiter`.
+pub(crate) enum Fields<'a> {
+    Whitespace(SplitWhitespace<'a>),
+    Char(Split<'a, char>),
+}
+
+impl<'a> Iterator for Fields<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Fields::Whitespace(iter) => iter.next(),
+            Fields::Char(iter) => iter.next().map(str::trim),
+        }
+    }
+}
+
+/// Reader of the content lines of a text format, reusing a single line buffer.
+///
+/// Blank lines and lines starting with the comment character are skipped.
+pub(crate) struct LineReader<R> {
+    reader: R,
+    buffer: String,
+    line: usize,
+    comment: char,
+}
+
+impl<R: BufRead> LineReader<R> {
+    pub(crate) fn new(reader: R, comment: char) -> Self {
+        Self {
+            reader,
+            buffer: String::new(),
+            line: 0,
+            comment,
+        }
+    }
+
+    /// Read the next content line and return it trimmed together with its 1-based number, or
+    /// `None` at the end of input.
+    pub(crate) fn next_line(&mut self) -> Option<Result<(usize, &str), Error>> {
+        loop {
+            self.buffer.clear();
+            match self.reader.read_line(&mut self.buffer) {
+                Ok(0) => return None,
+                Ok(_) => self.line += 1,
+                Err(err) => return Some(Err(err.into())),
+            }
+
+            let trimmed = self.buffer.trim();
+            let skip = trimmed.is_empty() || trimmed.starts_with(self.comment);
+            if !skip {
+                return Some(Ok((self.line, self.buffer.trim())));
+            }
+        }
+    }
+}
+
+impl fmt::Display for Delimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Delimiter::Whitespace => f.write_str(" "),
+            Delimiter::Char(c) => write!(f, "{}", c),
+        }
+    }
+}
+
+/// Item produced by a reader, which can be added to a graph.
+///
+/// Implemented for edges `(source, target, weight)` and for adjacency list lines
+/// `(node, neighbors)`.
+pub trait Record<N, E> {
+    /// Add the item to the graph, inserting its nodes automatically.
+    fn add_to<Ty, S>(self, graph: &mut Graph<N, E, Ty, S>)
+    where
+        N: NodeTrait,
+        Ty: EdgeType,
+        S: BuildHasher;
+}
+
+impl<N, E> Record<N, E> for (N, N, E) {
+    fn add_to<Ty, S>(self, graph: &mut Graph<N, E, Ty, S>)
+    where
+        N: NodeTrait,
+        Ty: EdgeType,
+        S: BuildHasher,
+    {
+        let (source, target, weight) = self;
+        graph.add_edge(source, target, weight);
+    }
+}
+
+/// A node without neighbors is added as well.
+impl<N, E> Record<N, E> for (N, Vec<(N, E)>) {
+    fn add_to<Ty, S>(self, graph: &mut Graph<N, E, Ty, S>)
+    where
+        N: NodeTrait,
+        Ty: EdgeType,
+        S: BuildHasher,
+    {
+        let (a, neighbors) = self;
+        graph.add_node(a);
+
+        for (b, weight) in neighbors {
+            graph.add_edge(a, b, weight);
+        }
+    }
+}
+
+/// Extend the graph from a fallible iterable of records, e.g. one of the readers.
+///
+/// Nodes are inserted automatically to match the edges.
+/// Stops at the first error, keeping the nodes and edges added so far.
+pub fn try_extend<N, E, Ty, S, T, I>(
+    graph: &mut Graph<N, E, Ty, S>,
+    iterable: I,
+) -> Result<(), Error>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+    S: BuildHasher,
+    T: Record<N, E>,
+    I: IntoIterator<Item = Result<T, Error>>,
+{
+    for elt in iterable {
+        elt?.add_to(graph);
+    }
+
+    Ok(())
+}
+
+/// Parse a single field, reporting the line number on failure.
+pub(crate) fn parse_field<T: FromStr>(field: &str, line: usize) -> Result<T, Error> {
+    field
+        .parse()
+        .map_err(|_| Error::parse(line, format!("invalid value `{}`", field)))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::io::{parse_field, Delimiter, Error, LineReader};
+    use std::io;
+
+    #[test]
+    fn error_display() {
+        assert_eq!(Error::parse(3, "oops").to_string(), "line 3: oops");
+        assert_eq!(
+            Error::Format("oops".to_string()).to_string(),
+            "invalid data: oops"
+        );
+
+        let err = Error::from(io::Error::other("boom"));
+        assert_eq!(err.to_string(), "I/O error: boom");
+    }
+
+    #[test]
+    fn delimiter_split() {
+        let fields: Vec<_> = Delimiter::Whitespace.split(" 1 \t2  3 ").collect();
+        assert_eq!(fields, vec!["1", "2", "3"]);
+
+        let fields: Vec<_> = Delimiter::Char(',').split("1, 2,3").collect();
+        assert_eq!(fields, vec!["1", "2", "3"]);
+    }
+
+    #[test]
+    fn line_reader() {
+        let data = "# comment\r\n1 2\r\n\n  \n 3 4 \n";
+        let mut lines = LineReader::new(data.as_bytes(), '#');
+
+        assert_eq!(lines.next_line().unwrap().unwrap(), (2, "1 2"));
+        assert_eq!(lines.next_line().unwrap().unwrap(), (5, "3 4"));
+        assert!(lines.next_line().is_none());
+    }
+
+    #[test]
+    fn parse_field_error() {
+        assert_eq!(parse_field::<u32>("12", 1).unwrap(), 12);
+
+        match parse_field::<u32>("x", 7) {
+            Err(Error::Parse { line, .. }) => assert_eq!(line, 7),
+            _ => panic!("Expected a parse error."),
+        }
+    }
+}
@@ -0,0 +1,370 @@
+//! Plain text adjacency list format.
+//!
+//! Every line starts with a node followed by its neighbors, each neighbor optionally carrying
+//! the weight of the edge as `neighbor:weight`. A line holding only a node describes a node
+//! without any (further) edges, so isolated nodes survive a round trip.
+//!
+//! For undirected graphs every edge is written once only, on the line of its smaller node.
+//!
+//! ```text
+//! # node neighbors...
+//! 1 2:0.5 3:1.5
+//! 2 3:2.5
+//! 3
+//! 4
+//! ```
+
+use crate::edge::EdgeType;
+use crate::graph::Graph;
+use crate::io::{parse_field, Delimiter, Error, LineReader};
+use crate::node::NodeTrait;
+use std::fmt::Display;
+use std::hash::BuildHasher;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+/// Configuration of the adjacency list format.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AdjacencyListFormat {
+    delimiter: Delimiter,
+    comment: char,
+    weight_separator: char,
+}
+
+impl AdjacencyListFormat {
+    /// Create a new whitespace separated format with `#` comments and `:` weight separator.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the field delimiter.
+    pub fn delimiter(mut self, delimiter: Delimiter) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Set the character starting comment lines.
+    pub fn comment(mut self, comment: char) -> Self {
+        self.comment = comment;
+        self
+    }
+
+    /// Set the character separating a neighbor from the edge weight.
+    pub fn weight_separator(mut self, weight_separator: char) -> Self {
+        self.weight_separator = weight_separator;
+        self
+    }
+}
+
+impl Default for AdjacencyListFormat {
+    fn default() -> Self {
+        Self {
+            delimiter: Delimiter::Whitespace,
+            comment: '#',
+            weight_separator: ':',
+        }
+    }
+}
+
+/// Parser of a single neighbor field into the neighbor and the edge weight.
+type NeighborParser<N, E> = fn(&str, char, usize) -> Result<(N, E), Error>;
+
+/// Streaming reader of an adjacency list.
+///
+/// Iterator element type is `Result<(N, Vec<(N, E)>), Error>`, i.e. a node with its
+/// neighbors and edge weights.
+pub struct AdjacencyListReader<R, N, E> {
+    lines: LineReader<R>,
+    format: AdjacencyListFormat,
+    parse: NeighborParser<N, E>,
+}
+
+impl<R, N, E> Iterator for AdjacencyListReader<R, N, E>
+where
+    R: BufRead,
+    N: FromStr,
+{
+    type Item = Result<(N, Vec<(N, E)>), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (number, line) = match self.lines.next_line()? {
+            Ok(line) => line,
+            Err(err) => return Some(Err(err)),
+        };
+
+        Some(parse_line(line, number, &self.format, self.parse))
+    }
+}
+
+/// Parse `node [neighbor[:weight]]...`.
+fn parse_line<N, E>(
+    line: &str,
+    number: usize,
+    format: &AdjacencyListFormat,
+    parse: NeighborParser<N, E>,
+) -> Result<(N, Vec<(N, E)>), Error>
+where
+    N: FromStr,
+{
+    let mut fields = format.delimiter.split(line);
+    let node = match fields.next() {
+        Some(field) => parse_field(field, number)?,
+        None => return Err(Error::parse(number, "missing node")),
+    };
+    let neighbors = fields
+        .filter(|field| !field.is_empty())
+        .map(|field| parse(field, format.weight_separator, number))
+        .collect::<Result<_, _>>()?;
+
+    Ok((node, neighbors))
+}
+
+/// Parse `neighbor[:weight]`, filling a missing weight with the default value.
+fn weighted_neighbor<N, E>(field: &str, separator: char, line: usize) -> Result<(N, E), Error>
+where
+    N: FromStr,
+    E: FromStr + Default,
+{
+    let mut parts = field.splitn(2, separator);
+    let node = parse_field(parts.next().unwrap_or_default(), line)?;
+    let weight = match parts.next() {
+        Some(weight) => parse_field(weight, line)?,
+        None => E::default(),
+    };
+
+    Ok((node, weight))
+}
+
+/// Parse `neighbor[:weight]`, ignoring the weight.
+fn unweighted_neighbor<N, E>(field: &str, separator: char, line: usize) -> Result<(N, E), Error>
+where
+    N: FromStr,
+    E: Default,
+{
+    let node = field.split(separator).next().unwrap_or_default();
+
+    Ok((parse_field(node, line)?, E::default()))
+}
+
+/// Read nodes with their neighbors and edge weights from an adjacency list.
+///
+/// Nodes without neighbors are added as well when the lines are passed to `io::try_extend`.
+///
+/// # Examples
+///
+/// ```
+/// use safe_graph::io::adjacency_list::{self, AdjacencyListFormat};
+/// use safe_graph::io::try_extend;
+/// use safe_graph::Graph;
+///
+/// let data = "1 2:0.5 3\n2 3:1.5\n4\n";
+/// let mut graph: Graph<u32, f32> = Graph::new();
+///
+/// try_extend(&mut graph, adjacency_list::read(data.as_bytes(), AdjacencyListFormat::new())).unwrap();
+///
+/// assert_eq!(graph.node_count(), 4);
+/// assert_eq!(graph.edge_weight(1, 2), Some(&0.5));
+/// assert_eq!(graph.edge_weight(1, 3), Some(&0.0));
+/// ```
+pub fn read<N, E, R>(reader: R, format: AdjacencyListFormat) -> AdjacencyListReader<R, N, E>
+where
+    N: FromStr,
+    E: FromStr + Default,
+    R: BufRead,
+{
+    AdjacencyListReader {
+        lines: LineReader::new(reader, format.comment),
+        format,
+        parse: weighted_neighbor::<N, E>,
+    }
+}
+
+/// Read nodes with their neighbors from an adjacency list, using default edge weights.
+pub fn read_unweighted<N, E, R>(
+    reader: R,
+    format: AdjacencyListFormat,
+) -> AdjacencyListReader<R, N, E>
+where
+    N: FromStr,
+    E: Default,
+    R: BufRead,
+{
+    AdjacencyListReader {
+        lines: LineReader::new(reader, format.comment),
+        format,
+        parse: unweighted_neighbor::<N, E>,
+    }
+}
+
+/// Write every node of the graph on its own line, followed by its neighbors and edge weights.
+///
+/// Wrap the writer into a `BufWriter` when writing to a file.
+pub fn write<N, E, Ty, S, W>(
+    graph: &Graph<N, E, Ty, S>,
+    writer: W,
+    format: AdjacencyListFormat,
+) -> Result<(), Error>
+where
+    N: NodeTrait + Display,
+    E: Display,
+    Ty: EdgeType,
+    S: BuildHasher,
+    W: Write,
+{
+    write_with(graph, writer, format, |writer, b, weight| {
+        write!(writer, "{}{}{}", b, format.weight_separator, weight)
+    })
+}
+
+/// Write every node of the graph on its own line, followed by its neighbors.
+///
+/// Wrap the writer into a `BufWriter` when writing to a file.
+pub fn write_unweighted<N, E, Ty, S, W>(
+    graph: &Graph<N, E, Ty, S>,
+    writer: W,
+    format: AdjacencyListFormat,
+) -> Result<(), Error>
+where
+    N: NodeTrait + Display,
+    Ty: EdgeType,
+    S: BuildHasher,
+    W: Write,
+{
+    write_with(graph, writer, format, |writer, b, _| {
+        write!(writer, "{}", b)
+    })
+}
+
+fn write_with<N, E, Ty, S, W, F>(
+    graph: &Graph<N, E, Ty, S>,
+    mut writer: W,
+    format: AdjacencyListFormat,
+    mut neighbor: F,
+) -> Result<(), Error>
+where
+    N: NodeTrait + Display,
+    Ty: EdgeType,
+    S: BuildHasher,
+    W: Write,
+    F: FnMut(&mut W, N, &E) -> std::io::Result<()>,
+{
+    for a in graph.nodes() {
+        write!(writer, "{}", a)?;
+
+        for (_, b, weight) in graph.edges(a) {
+            // Undirected edges are listed by both nodes, write them only once.
+            if Graph::<N, E, Ty>::edge_key(a, b) != (a, b) {
+                continue;
+            }
+
+            write!(writer, "{}", format.delimiter)?;
+            neighbor(&mut writer, b, weight)?;
+        }
+
+        writeln!(writer)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{Graph, Undirected};
+    use crate::io::adjacency_list::{self, AdjacencyListFormat};
+    use crate::io::{try_extend, Delimiter, Error};
+
+    #[test]
+    fn format_builder() {
+        let format = AdjacencyListFormat::new()
+            .delimiter(Delimiter::Char(','))
+            .comment('%')
+            .weight_separator('=');
+
+        assert_eq!(format.delimiter, Delimiter::Char(','));
+        assert_eq!(format.comment, '%');
+        assert_eq!(format.weight_separator, '=');
+    }
+
+    #[test]
+    fn read() {
+        let data = "# comment\n1 2:0.5 3\n\n2 3:2.5\n4\n";
+        let lines: Vec<(u32, Vec<(u32, f32)>)> =
+            adjacency_list::read(data.as_bytes(), AdjacencyListFormat::new())
+                .collect::<Result<_, _>>()
+                .unwrap();
+
+        assert_eq!(
+            lines,
+            vec![
+                (1, vec![(2, 0.5), (3, 0.0)]),
+                (2, vec![(3, 2.5)]),
+                (4, vec![]),
+            ]
+        );
+    }
+
+    #[test]
+    fn read_errors() {
+        let data = "1 2:x\nx 2\n1 2:3\n";
+        let lines: Vec<usize> =
+            adjacency_list::read::<u32, u32, _>(data.as_bytes(), AdjacencyListFormat::new())
+                .filter_map(|line| match line {
+                    Err(Error::Parse { line, .. }) => Some(line),
+                    _ => None,
+                })
+                .collect();
+
+        assert_eq!(lines, vec![1, 2]);
+    }
+
+    #[test]
+    fn try_extend_graph() {
+        let data = "1,2=4,3\n5\n";
+        let format = AdjacencyListFormat::new()
+            .delimiter(Delimiter::Char(','))
+            .weight_separator('=');
+        let mut graph: Graph<u32, u32> = Graph::new();
+
+        try_extend(&mut graph, adjacency_list::read(data.as_bytes(), format)).unwrap();
+
+        assert_eq!(graph.node_count(), 4);
+        assert_eq!(graph.edge_weight(1, 2), Some(&4));
+        assert_eq!(graph.edge_weight(1, 3), Some(&0));
+        assert!(graph.contains_node(5));
+    }
+
+    #[test]
+    fn write() {
+        let mut graph = Graph::<u32, f32>::from_edges(&[(1, 2, 0.5), (1, 3, 1.5), (3, 1, 2.0)]);
+        graph.add_node(4);
+        let mut out = Vec::new();
+
+        adjacency_list::write(&graph, &mut out, AdjacencyListFormat::new()).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "1 2:0.5 3:1.5\n2\n3 1:2\n4\n"
+        );
+    }
+
+    #[test]
+    fn write_unweighted_undirected() {
+        let graph = Graph::<u32, (), Undirected>::from_edges(&[(2, 1), (3, 2), (3, 3)]);
+        let mut out = Vec::new();
+
+        adjacency_list::write_unweighted(&graph, &mut out, AdjacencyListFormat::new()).unwrap();
+
+        assert_eq!(String::from_utf8(out.clone()).unwrap(), "2 3\n1 2\n3 3\n");
+
+        // Read it back.
+        let mut read: Graph<u32, (), Undirected> = Graph::new();
+        let reader = adjacency_list::read_unweighted(&out[..], AdjacencyListFormat::new());
+        try_extend(&mut read, reader).unwrap();
+
+        assert_eq!(read.node_count(), 3);
+        assert_eq!(read.edge_count(), 3);
+        assert!(read.contains_edge(1, 2));
+        assert!(read.contains_edge(3, 2));
+        assert!(read.contains_edge(3, 3));
+    }
+}
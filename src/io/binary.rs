@@ -0,0 +1,563 @@
+//! Compact binary snapshot format.
+//!
+//! The snapshot is laid out as follows:
+//!
+//! - Magic bytes `SGRF` and the format version.
+//! - Flags byte, bit `0` being set for directed graphs.
+//! - Node table: varint encoded node count followed by the encoded nodes.
+//! - Edge table: varint encoded edge count followed by the edges, every edge being stored as
+//!   the varint encoded positions of its nodes in the node table and the encoded weight.
+//! - CRC-32 checksum of all the preceding bytes.
+//!
+//! Nodes and edge weights are encoded by a `Codec`, the `Native` codec covering primitive types.
+
+use crate::edge::EdgeType;
+use crate::graph::Graph;
+use crate::io::Error;
+use crate::node::NodeTrait;
+use std::hash::BuildHasher;
+use std::io::{self, Read, Write};
+
+/// Magic bytes starting every snapshot.
+const MAGIC: &[u8; 4] = b"SGRF";
+
+/// Current version of the format.
+const VERSION: u8 = 1;
+
+/// Flag marking a directed graph.
+const DIRECTED: u8 = 0x1;
+
+/// Encoding and decoding of values of type `T`.
+pub trait Codec<T> {
+    /// Write the value to the writer.
+    fn encode<W: Write>(&self, value: &T, writer: &mut W) -> io::Result<()>;
+
+    /// Read a value from the reader.
+    fn decode<R: Read>(&self, reader: &mut R) -> io::Result<T>;
+}
+
+/// Codec of primitive types, numbers being stored in little endian byte order.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct Native;
+
+macro_rules! native_codec {
+    ($($ty:ty),*) => {
+        $(
+            impl Codec<$ty> for Native {
+                fn encode<W: Write>(&self, value: &$ty, writer: &mut W) -> io::Result<()> {
+                    writer.write_all(&value.to_le_bytes())
+                }
+
+                fn decode<R: Read>(&self, reader: &mut R) -> io::Result<$ty> {
+                    let mut bytes = [0; std::mem::size_of::<$ty>()];
+                    reader.read_exact(&mut bytes)?;
+                    Ok(<$ty>::from_le_bytes(bytes))
+                }
+            }
+        )*
+    };
+}
+
+native_codec!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128, f32, f64);
+
+impl Codec<()> for Native {
+    fn encode<W: Write>(&self, _value: &(), _writer: &mut W) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn decode<R: Read>(&self, _reader: &mut R) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl Codec<bool> for Native {
+    fn encode<W: Write>(&self, value: &bool, writer: &mut W) -> io::Result<()> {
+        writer.write_all(&[*value as u8])
+    }
+
+    fn decode<R: Read>(&self, reader: &mut R) -> io::Result<bool> {
+        match Codec::<u8>::decode(self, reader)? {
+            0 => Ok(false),
+            1 => Ok(true),
+            _ => Err(invalid_data("invalid bool")),
+        }
+    }
+}
+
+impl Codec<char> for Native {
+    fn encode<W: Write>(&self, value: &char, writer: &mut W) -> io::Result<()> {
+        self.encode(&(*value as u32), writer)
+    }
+
+    fn decode<R: Read>(&self, reader: &mut R) -> io::Result<char> {
+        let value: u32 = self.decode(reader)?;
+        std::char::from_u32(value).ok_or_else(|| invalid_data("invalid char"))
+    }
+}
+
+impl Codec<String> for Native {
+    fn encode<W: Write>(&self, value: &String, writer: &mut W) -> io::Result<()> {
+        write_varint(writer, value.len() as u64)?;
+        writer.write_all(value.as_bytes())
+    }
+
+    fn decode<R: Read>(&self, reader: &mut R) -> io::Result<String> {
+        let len = read_varint(reader)?;
+        let mut bytes = Vec::new();
+        reader.take(len).read_to_end(&mut bytes)?;
+        if bytes.len() as u64 != len {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+
+        String::from_utf8(bytes).map_err(|_| invalid_data("invalid UTF-8 string"))
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Write an unsigned LEB128 varint.
+fn write_varint<W: Write>(writer: &mut W, mut value: u64) -> io::Result<()> {
+    let mut bytes = [0; 10];
+    let mut len = 0;
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            bytes[len] = byte;
+            len += 1;
+            break;
+        }
+        bytes[len] = byte | 0x80;
+        len += 1;
+    }
+
+    writer.write_all(&bytes[..len])
+}
+
+/// Read an unsigned LEB128 varint.
+fn read_varint<R: Read>(reader: &mut R) -> io::Result<u64> {
+    let mut value = 0;
+    for shift in (0..64).step_by(7) {
+        let mut byte = [0];
+        reader.read_exact(&mut byte)?;
+        value |= u64::from(byte[0] & 0x7f) << shift;
+        if byte[0] & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+
+    Err(invalid_data("varint overflow"))
+}
+
+/// Lookup table of the CRC-32 (IEEE) checksum.
+const CRC_TABLE: [u32; 256] = crc_table();
+
+const fn crc_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut crc = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+            bit += 1;
+        }
+        table[i] = crc;
+        i += 1;
+    }
+    table
+}
+
+/// Running CRC-32 checksum.
+struct Crc32(u32);
+
+impl Crc32 {
+    fn new() -> Self {
+        Crc32(0xffff_ffff)
+    }
+
+    fn update(&mut self, bytes: &[u8]) {
+        for &byte in bytes {
+            self.0 = CRC_TABLE[((self.0 ^ u32::from(byte)) & 0xff) as usize] ^ (self.0 >> 8);
+        }
+    }
+
+    fn finish(&self) -> u32 {
+        !self.0
+    }
+}
+
+/// Writer computing the checksum of all written bytes.
+struct ChecksumWriter<W> {
+    inner: W,
+    crc: Crc32,
+}
+
+impl<W: Write> Write for ChecksumWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.crc.update(&buf[..written]);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Reader computing the checksum of all read bytes.
+struct ChecksumReader<R> {
+    inner: R,
+    crc: Crc32,
+}
+
+impl<R: Read> Read for ChecksumReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.crc.update(&buf[..read]);
+        Ok(read)
+    }
+}
+
+impl<N, E, Ty, S> Graph<N, E, Ty, S>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    /// Write the graph as a binary snapshot, encoding nodes and edge weights by the `Native`
+    /// codec.
+    ///
+    /// Wrap the writer into a `BufWriter` when writing to a file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use safe_graph::Graph;
+    ///
+    /// let graph = Graph::<u32, f32>::from_edges(&[(1, 2, 0.5), (2, 3, 1.5)]);
+    /// let mut snapshot = Vec::new();
+    /// graph.write_to(&mut snapshot).unwrap();
+    ///
+    /// let read: Graph<u32, f32> = Graph::read_from(&snapshot[..]).unwrap();
+    ///
+    /// assert_eq!(read.edge_weight(2, 3), Some(&1.5));
+    /// ```
+    pub fn write_to<W>(&self, writer: W) -> Result<(), Error>
+    where
+        W: Write,
+        Native: Codec<N> + Codec<E>,
+    {
+        self.write_to_with(writer, &Native, &Native)
+    }
+
+    /// Write the graph as a binary snapshot, encoding nodes and edge weights by the given codecs.
+    ///
+    /// Wrap the writer into a `BufWriter` when writing to a file.
+    pub fn write_to_with<W, NC, EC>(
+        &self,
+        writer: W,
+        node_codec: &NC,
+        edge_codec: &EC,
+    ) -> Result<(), Error>
+    where
+        W: Write,
+        NC: Codec<N>,
+        EC: Codec<E>,
+    {
+        let mut writer = ChecksumWriter {
+            inner: writer,
+            crc: Crc32::new(),
+        };
+
+        writer.write_all(MAGIC)?;
+        let flags = if Ty::is_directed() { DIRECTED } else { 0 };
+        writer.write_all(&[VERSION, flags])?;
+
+        write_varint(&mut writer, self.node_count() as u64)?;
+        for n in self.nodes() {
+            node_codec.encode(&n, &mut writer)?;
+        }
+
+        write_varint(&mut writer, self.edge_count() as u64)?;
+        for (a, b, weight) in self.all_edges() {
+            // Edges are only ever stored between existing nodes.
+            write_varint(&mut writer, self.node_index(a).unwrap() as u64)?;
+            write_varint(&mut writer, self.node_index(b).unwrap() as u64)?;
+            edge_codec.encode(weight, &mut writer)?;
+        }
+
+        let checksum = writer.crc.finish();
+        writer.inner.write_all(&checksum.to_le_bytes())?;
+        writer.inner.flush()?;
+
+        Ok(())
+    }
+}
+
+impl<N, E, Ty, S> Graph<N, E, Ty, S>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+    S: BuildHasher + Default,
+{
+    /// Read a graph from a binary snapshot, decoding nodes and edge weights by the `Native`
+    /// codec.
+    ///
+    /// Wrap the reader into a `BufReader` when reading from a file.
+    pub fn read_from<R>(reader: R) -> Result<Self, Error>
+    where
+        R: Read,
+        Native: Codec<N> + Codec<E>,
+    {
+        Self::read_from_with(reader, &Native, &Native)
+    }
+
+    /// Read a graph from a binary snapshot, decoding nodes and edge weights by the given codecs.
+    ///
+    /// The snapshot must have been written with the same edge type `Ty` and checksum has to
+    /// match the content, otherwise `Error::Format` is returned.
+    ///
+    /// Wrap the reader into a `BufReader` when reading from a file.
+    pub fn read_from_with<R, NC, EC>(
+        reader: R,
+        node_codec: &NC,
+        edge_codec: &EC,
+    ) -> Result<Self, Error>
+    where
+        R: Read,
+        NC: Codec<N>,
+        EC: Codec<E>,
+    {
+        let mut reader = ChecksumReader {
+            inner: reader,
+            crc: Crc32::new(),
+        };
+
+        let mut header = [0; 6];
+        reader.read_exact(&mut header)?;
+        if &header[..4] != MAGIC {
+            return Err(Error::Format("not a graph snapshot".to_string()));
+        }
+        if header[4] != VERSION {
+            return Err(Error::Format(format!(
+                "unsupported version `{}`",
+                header[4]
+            )));
+        }
+        if (header[5] & DIRECTED == DIRECTED) != Ty::is_directed() {
+            return Err(Error::Format("edge type mismatch".to_string()));
+        }
+
+        // Don't trust the counts for preallocation, the data could be corrupted.
+        let node_count = read_varint(&mut reader)?;
+        let mut nodes = Vec::with_capacity(node_count.min(1 << 16) as usize);
+        for _ in 0..node_count {
+            nodes.push(node_codec.decode(&mut reader)?);
+        }
+
+        let edge_count = read_varint(&mut reader)?;
+        let mut graph = Self::with_capacity_default(nodes.len(), edge_count.min(1 << 16) as usize);
+        for &n in nodes.iter() {
+            graph.add_node(n);
+        }
+
+        for _ in 0..edge_count {
+            let a = read_varint(&mut reader)?;
+            let b = read_varint(&mut reader)?;
+            let weight = edge_codec.decode(&mut reader)?;
+
+            match (nodes.get(a as usize), nodes.get(b as usize)) {
+                (Some(&a), Some(&b)) => graph.add_edge(a, b, weight),
+                _ => return Err(Error::Format("node position out of bounds".to_string())),
+            };
+        }
+
+        let expected = reader.crc.finish();
+        let mut checksum = [0; 4];
+        reader.inner.read_exact(&mut checksum)?;
+        if u32::from_le_bytes(checksum) != expected {
+            return Err(Error::Format("checksum mismatch".to_string()));
+        }
+
+        Ok(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{Graph, Undirected, UndirectedGraph};
+    use crate::io::binary::{read_varint, write_varint, Codec, Crc32, Native};
+    use crate::io::Error;
+    use std::collections::hash_map::DefaultHasher;
+    use std::hash::BuildHasherDefault;
+    use std::io::{self, Read, Write};
+
+    #[test]
+    fn varint() {
+        for &value in [0, 1, 127, 128, 300, u64::from(u32::MAX), u64::MAX].iter() {
+            let mut bytes = Vec::new();
+            write_varint(&mut bytes, value).unwrap();
+
+            assert_eq!(read_varint(&mut &bytes[..]).unwrap(), value);
+        }
+
+        let mut bytes = Vec::new();
+        write_varint(&mut bytes, 300).unwrap();
+        assert_eq!(bytes, vec![0xac, 0x02]);
+    }
+
+    #[test]
+    fn crc32() {
+        let mut crc = Crc32::new();
+        crc.update(b"123456789");
+
+        assert_eq!(crc.finish(), 0xcbf4_3926);
+    }
+
+    #[test]
+    fn native_codec() {
+        let mut bytes = Vec::new();
+        Native.encode(&-5i32, &mut bytes).unwrap();
+        Native.encode(&true, &mut bytes).unwrap();
+        Native.encode(&'ž', &mut bytes).unwrap();
+        Native.encode(&"text".to_string(), &mut bytes).unwrap();
+        Native.encode(&2.5f64, &mut bytes).unwrap();
+
+        let mut reader = &bytes[..];
+        assert_eq!(Codec::<i32>::decode(&Native, &mut reader).unwrap(), -5);
+        assert!(Codec::<bool>::decode(&Native, &mut reader).unwrap());
+        assert_eq!(Codec::<char>::decode(&Native, &mut reader).unwrap(), 'ž');
+        assert_eq!(
+            Codec::<String>::decode(&Native, &mut reader).unwrap(),
+            "text"
+        );
+        assert_eq!(Codec::<f64>::decode(&Native, &mut reader).unwrap(), 2.5);
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut graph = Graph::<u32, f32>::from_edges(&[(5, 1, 0.5), (1, 7, 1.5), (7, 7, 2.5)]);
+        graph.add_node(9);
+        let mut snapshot = Vec::new();
+        graph.write_to(&mut snapshot).unwrap();
+
+        let read: Graph<u32, f32> = Graph::read_from(&snapshot[..]).unwrap();
+
+        assert_eq!(read.nodes().collect::<Vec<_>>(), vec![5, 1, 7, 9]);
+        assert_eq!(
+            read.all_edges().collect::<Vec<_>>(),
+            graph.all_edges().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn round_trip_undirected_unweighted() {
+        let graph = Graph::<i64, (), Undirected>::from_edges(&[(-1, 2), (2, 3)]);
+        let mut snapshot = Vec::new();
+        graph.write_to(&mut snapshot).unwrap();
+
+        let read: Graph<i64, (), Undirected> = Graph::read_from(&snapshot[..]).unwrap();
+
+        assert_eq!(read.edge_count(), 2);
+        assert!(read.contains_edge(2, -1));
+        assert!(read.contains_edge(3, 2));
+    }
+
+    #[test]
+    fn round_trip_with_hasher() {
+        type Hasher = BuildHasherDefault<DefaultHasher>;
+
+        let graph = UndirectedGraph::<u32, (), Hasher>::from_edges(&[(1, 2), (2, 3)]);
+        let mut snapshot = Vec::new();
+        graph.write_to(&mut snapshot).unwrap();
+
+        let read: UndirectedGraph<u32, (), Hasher> = Graph::read_from(&snapshot[..]).unwrap();
+
+        assert_eq!(read.edge_count(), 2);
+        assert!(read.contains_edge(3, 2));
+    }
+
+    /// Codec storing strings as a single length byte and the bytes.
+    struct ShortString;
+
+    impl Codec<String> for ShortString {
+        fn encode<W: Write>(&self, value: &String, writer: &mut W) -> io::Result<()> {
+            writer.write_all(&[value.len() as u8])?;
+            writer.write_all(value.as_bytes())
+        }
+
+        fn decode<R: Read>(&self, reader: &mut R) -> io::Result<String> {
+            let mut len = [0];
+            reader.read_exact(&mut len)?;
+            let mut bytes = vec![0; len[0] as usize];
+            reader.read_exact(&mut bytes)?;
+            Ok(String::from_utf8(bytes).unwrap())
+        }
+    }
+
+    #[test]
+    fn round_trip_custom_codec() {
+        let graph = Graph::<u8, String>::from_edges(vec![(1, 2, "a".to_string())]);
+        let mut snapshot = Vec::new();
+        graph
+            .write_to_with(&mut snapshot, &Native, &ShortString)
+            .unwrap();
+
+        let read: Graph<u8, String> =
+            Graph::read_from_with(&snapshot[..], &Native, &ShortString).unwrap();
+
+        assert_eq!(read.edge_weight(1, 2), Some(&"a".to_string()));
+    }
+
+    #[test]
+    fn read_errors() {
+        let graph = Graph::<u32, u32>::from_edges(&[(1, 2, 3)]);
+        let mut snapshot = Vec::new();
+        graph.write_to(&mut snapshot).unwrap();
+
+        // Wrong magic bytes.
+        let mut data = snapshot.clone();
+        data[0] = b'X';
+        assert!(matches!(
+            Graph::<u32, u32>::read_from(&data[..]),
+            Err(Error::Format(_))
+        ));
+
+        // Unsupported version.
+        let mut data = snapshot.clone();
+        data[4] = 2;
+        assert!(matches!(
+            Graph::<u32, u32>::read_from(&data[..]),
+            Err(Error::Format(_))
+        ));
+
+        // Edge type mismatch.
+        assert!(matches!(
+            Graph::<u32, u32, Undirected>::read_from(&snapshot[..]),
+            Err(Error::Format(_))
+        ));
+
+        // Corrupted edge weight.
+        let mut data = snapshot.clone();
+        let len = data.len();
+        data[len - 5] ^= 0xff;
+        assert!(matches!(
+            Graph::<u32, u32>::read_from(&data[..]),
+            Err(Error::Format(_))
+        ));
+
+        // Truncated data.
+        assert!(matches!(
+            Graph::<u32, u32>::read_from(&snapshot[..snapshot.len() - 1]),
+            Err(Error::Io(_))
+        ));
+    }
+}
@@ -0,0 +1,376 @@
+//! Export of a `Graph` as a Mermaid or PlantUML diagram.
+//!
+//! Both exporters implement `Display`, so the diagram can be written by `format!`, `write!`
+//! or `to_string()`. Nodes are labelled by their `Display` representation and edges are left
+//! unlabelled, unless custom label closures are provided.
+
+use crate::edge::EdgeType;
+use crate::graph::Graph;
+use crate::node::NodeTrait;
+use std::collections::hash_map::RandomState;
+use std::fmt::{self, Display};
+use std::hash::BuildHasher;
+
+/// Direction in which the diagram is laid out.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Layout {
+    /// Top to bottom, `TD`.
+    TopDown,
+    /// Bottom to top, `BT`.
+    BottomUp,
+    /// Left to right, `LR`.
+    LeftRight,
+    /// Right to left, `RL`.
+    RightLeft,
+}
+
+type NodeLabel<'a, N> = Box<dyn Fn(N) -> String + 'a>;
+type EdgeLabel<'a, E> = Box<dyn Fn(&E) -> String + 'a>;
+
+/// Mermaid `flowchart` of a graph.
+///
+/// # Examples
+///
+/// ```
+/// use safe_graph::io::diagram::{Layout, Mermaid};
+/// use safe_graph::Graph;
+///
+/// let graph = Graph::<_, _>::from_edges(&[(1, 2, 0.5), (2, 3, 1.5)]);
+/// let mermaid = Mermaid::new(&graph)
+///     .layout(Layout::LeftRight)
+///     .edge_label(|weight| weight.to_string());
+///
+/// assert_eq!(
+///     mermaid.to_string(),
+///     "flowchart LR\n    n0[\"1\"]\n    n1[\"2\"]\n    n2[\"3\"]\n    \
+///      n0 -->|\"0.5\"| n1\n    n1 -->|\"1.5\"| n2\n"
+/// );
+/// ```
+pub struct Mermaid<'a, N, E, Ty, S = RandomState> {
+    graph: &'a Graph<N, E, Ty, S>,
+    layout: Layout,
+    fenced: bool,
+    node_label: NodeLabel<'a, N>,
+    edge_label: Option<EdgeLabel<'a, E>>,
+}
+
+impl<'a, N, E, Ty, S> Mermaid<'a, N, E, Ty, S>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    /// Create a new top down Mermaid diagram of the graph, nodes being labelled by their
+    /// `Display` representation.
+    pub fn new(graph: &'a Graph<N, E, Ty, S>) -> Self
+    where
+        N: Display,
+    {
+        Self::with_node_label(graph, |n| n.to_string())
+    }
+
+    /// Create a new top down Mermaid diagram of the graph, nodes being labelled by `label`.
+    ///
+    /// Nodes not implementing `Display` can be labelled e.g. by `|n| format!("{:?}", n)`.
+    pub fn with_node_label<F>(graph: &'a Graph<N, E, Ty, S>, label: F) -> Self
+    where
+        F: Fn(N) -> String + 'a,
+    {
+        Self {
+            graph,
+            layout: Layout::TopDown,
+            fenced: false,
+            node_label: Box::new(label),
+            edge_label: None,
+        }
+    }
+
+    /// Set the direction of the diagram.
+    pub fn layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Wrap the diagram into a Markdown ` ```mermaid ` code block.
+    pub fn fenced(mut self, fenced: bool) -> Self {
+        self.fenced = fenced;
+        self
+    }
+
+    /// Set the closure producing node labels.
+    pub fn node_label<F>(mut self, label: F) -> Self
+    where
+        F: Fn(N) -> String + 'a,
+    {
+        self.node_label = Box::new(label);
+        self
+    }
+
+    /// Set the closure producing edge labels.
+    pub fn edge_label<F>(mut self, label: F) -> Self
+    where
+        F: Fn(&E) -> String + 'a,
+    {
+        self.edge_label = Some(Box::new(label));
+        self
+    }
+}
+
+impl<'a, N, E, Ty, S> fmt::Display for Mermaid<'a, N, E, Ty, S>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let layout = match self.layout {
+            Layout::TopDown => "TD",
+            Layout::BottomUp => "BT",
+            Layout::LeftRight => "LR",
+            Layout::RightLeft => "RL",
+        };
+        let arrow = if Ty::is_directed() { "-->" } else { "---" };
+
+        if self.fenced {
+            writeln!(f, "```mermaid")?;
+        }
+        writeln!(f, "flowchart {}", layout)?;
+
+        for (i, n) in self.graph.nodes().enumerate() {
+            let label = escape_mermaid((self.node_label)(n));
+            writeln!(f, "    n{}[\"{}\"]", i, label)?;
+        }
+
+        for (a, b, weight) in self.graph.all_edges() {
+            let (a, b) = (node_id(self.graph, a), node_id(self.graph, b));
+            match self.edge_label {
+                Some(ref edge_label) => {
+                    let label = escape_mermaid(edge_label(weight));
+                    writeln!(f, "    n{} {}|\"{}\"| n{}", a, arrow, label, b)?;
+                }
+                None => writeln!(f, "    n{} {} n{}", a, arrow, b)?,
+            }
+        }
+
+        if self.fenced {
+            writeln!(f, "```")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// PlantUML diagram of a graph, nodes being drawn as rectangles.
+///
+/// # Examples
+///
+/// ```
+/// use safe_graph::io::diagram::PlantUml;
+/// use safe_graph::UndirectedGraph;
+///
+/// let graph = UndirectedGraph::<_, ()>::from_edges(&[("a", "b")]);
+/// let plantuml = PlantUml::new(&graph).node_label(|n| n.to_uppercase());
+///
+/// assert_eq!(
+///     plantuml.to_string(),
+///     "@startuml\nrectangle \"A\" as n0\nrectangle \"B\" as n1\nn0 -- n1\n@enduml\n"
+/// );
+/// ```
+pub struct PlantUml<'a, N, E, Ty, S = RandomState> {
+    graph: &'a Graph<N, E, Ty, S>,
+    layout: Layout,
+    node_label: NodeLabel<'a, N>,
+    edge_label: Option<EdgeLabel<'a, E>>,
+}
+
+impl<'a, N, E, Ty, S> PlantUml<'a, N, E, Ty, S>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    /// Create a new top down PlantUML diagram of the graph, nodes being labelled by their
+    /// `Display` representation.
+    pub fn new(graph: &'a Graph<N, E, Ty, S>) -> Self
+    where
+        N: Display,
+    {
+        Self::with_node_label(graph, |n| n.to_string())
+    }
+
+    /// Create a new top down PlantUML diagram of the graph, nodes being labelled by `label`.
+    ///
+    /// Nodes not implementing `Display` can be labelled e.g. by `|n| format!("{:?}", n)`.
+    pub fn with_node_label<F>(graph: &'a Graph<N, E, Ty, S>, label: F) -> Self
+    where
+        F: Fn(N) -> String + 'a,
+    {
+        Self {
+            graph,
+            layout: Layout::TopDown,
+            node_label: Box::new(label),
+            edge_label: None,
+        }
+    }
+
+    /// Set the direction of the diagram.
+    ///
+    /// PlantUML supports top down and left to right layouts only, `BottomUp` and `RightLeft`
+    /// fall back to them.
+    pub fn layout(mut self, layout: Layout) -> Self {
+        self.layout = layout;
+        self
+    }
+
+    /// Set the closure producing node labels.
+    pub fn node_label<F>(mut self, label: F) -> Self
+    where
+        F: Fn(N) -> String + 'a,
+    {
+        self.node_label = Box::new(label);
+        self
+    }
+
+    /// Set the closure producing edge labels.
+    pub fn edge_label<F>(mut self, label: F) -> Self
+    where
+        F: Fn(&E) -> String + 'a,
+    {
+        self.edge_label = Some(Box::new(label));
+        self
+    }
+}
+
+impl<'a, N, E, Ty, S> fmt::Display for PlantUml<'a, N, E, Ty, S>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let arrow = if Ty::is_directed() { "-->" } else { "--" };
+
+        writeln!(f, "@startuml")?;
+        if let Layout::LeftRight | Layout::RightLeft = self.layout {
+            writeln!(f, "left to right direction")?;
+        }
+
+        for (i, n) in self.graph.nodes().enumerate() {
+            let label = escape((self.node_label)(n), "'");
+            writeln!(f, "rectangle \"{}\" as n{}", label, i)?;
+        }
+
+        for (a, b, weight) in self.graph.all_edges() {
+            let (a, b) = (node_id(self.graph, a), node_id(self.graph, b));
+            write!(f, "n{} {} n{}", a, arrow, b)?;
+            if let Some(ref edge_label) = self.edge_label {
+                write!(f, " : {}", escape(edge_label(weight), "'"))?;
+            }
+            writeln!(f)?;
+        }
+
+        writeln!(f, "@enduml")
+    }
+}
+
+/// Return the position of node `n` in the graph, used as the diagram identifier.
+fn node_id<N, E, Ty, S>(graph: &Graph<N, E, Ty, S>, n: N) -> usize
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    graph
+        .node_index(n)
+        .expect("Edge node is not part of the graph.")
+}
+
+/// Replace quotes and line breaks, which would break the diagram syntax.
+fn escape(label: String, quote: &str) -> String {
+    label.replace('"', quote).replace('\n', "\\n")
+}
+
+/// Escape a Mermaid label, the pipe delimiting edge labels included.
+fn escape_mermaid(label: String) -> String {
+    escape(label, "#quot;").replace('|', "#124;")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{Graph, Undirected};
+    use crate::io::diagram::{escape, Layout, Mermaid, PlantUml};
+
+    #[test]
+    fn mermaid_default() {
+        let mut graph = Graph::<&str, ()>::from_edges(&[("a", "b"), ("b", "b")]);
+        graph.add_node("c");
+
+        assert_eq!(
+            Mermaid::new(&graph).to_string(),
+            "flowchart TD\n    n0[\"a\"]\n    n1[\"b\"]\n    n2[\"c\"]\n    \
+             n0 --> n1\n    n1 --> n1\n"
+        );
+    }
+
+    #[test]
+    fn mermaid_fenced_undirected() {
+        let graph = Graph::<u32, u32, Undirected>::from_edges(&[(2, 1, 7)]);
+        let mermaid = Mermaid::new(&graph)
+            .layout(Layout::BottomUp)
+            .fenced(true)
+            .node_label(|n| format!("node {}", n))
+            .edge_label(|w| format!("w={}", w));
+
+        assert_eq!(
+            mermaid.to_string(),
+            "```mermaid\nflowchart BT\n    n0[\"node 2\"]\n    n1[\"node 1\"]\n    \
+             n1 ---|\"w=7\"| n0\n```\n"
+        );
+    }
+
+    #[test]
+    fn mermaid_layout() {
+        let graph: Graph<u32, ()> = Graph::new();
+
+        assert_eq!(
+            Mermaid::new(&graph).layout(Layout::RightLeft).to_string(),
+            "flowchart RL\n"
+        );
+        assert_eq!(
+            Mermaid::new(&graph).layout(Layout::LeftRight).to_string(),
+            "flowchart LR\n"
+        );
+    }
+
+    #[test]
+    fn plantuml_directed() {
+        let graph = Graph::<u32, f32>::from_edges(&[(1, 2, 0.5)]);
+        let plantuml = PlantUml::new(&graph)
+            .layout(Layout::LeftRight)
+            .edge_label(|w| w.to_string());
+
+        assert_eq!(
+            plantuml.to_string(),
+            "@startuml\nleft to right direction\nrectangle \"1\" as n0\n\
+             rectangle \"2\" as n1\nn0 --> n1 : 0.5\n@enduml\n"
+        );
+    }
+
+    #[test]
+    fn mermaid_escape_label() {
+        let graph = Graph::<u32, &str>::from_edges(&[(1, 2, "a|\"b\"")]);
+        let mermaid = Mermaid::with_node_label(&graph, |n| format!("{:?}", Some(n)))
+            .edge_label(|w| w.to_string());
+
+        assert_eq!(
+            mermaid.to_string(),
+            "flowchart TD\n    n0[\"Some(1)\"]\n    n1[\"Some(2)\"]\n    \
+             n0 -->|\"a#124;#quot;b#quot;\"| n1\n"
+        );
+    }
+
+    #[test]
+    fn escape_label() {
+        assert_eq!(escape("a \"b\"\nc".to_string(), "'"), "a 'b'\\nc");
+    }
+}
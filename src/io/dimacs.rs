@@ -0,0 +1,344 @@
+//! DIMACS shortest path and maximum flow formats.
+//!
+//! A problem line `p sp nodes arcs` or `p max nodes arcs` is followed by the arcs as
+//! `a source target weight`. Maximum flow problems also designate their source and sink nodes
+//! by `n id s` and `n id t` lines. Lines starting with `c` are comments.
+//!
+//! DIMACS node ids are 1-based, node `n` of the graph is stored as `n + 1`. An undirected edge
+//! is written as a pair of opposite arcs.
+//!
+//! The node count of the problem line only bounds the node ids. Nodes are added as they are
+//! referenced by the arcs or the source and sink lines, so nodes without any arc are not part of
+//! the read graph.
+//!
+//! ```text
+//! c Shortest path problem
+//! p sp 3 2
+//! a 1 2 10
+//! a 2 3 20
+//! ```
+
+use crate::edge::EdgeType;
+use crate::graph::Graph;
+use crate::io::{parse_field, Error};
+use std::fmt::Display;
+use std::hash::BuildHasher;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+/// Problem described by a DIMACS file.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Problem {
+    /// Shortest path problem `p sp`, edge weights are arc lengths.
+    ShortestPath,
+    /// Maximum flow problem `p max`, edge weights are arc capacities.
+    MaxFlow { source: u32, sink: u32 },
+}
+
+/// Graph of a DIMACS file together with its problem.
+type Instance<E, Ty, S> = (Graph<u32, E, Ty, S>, Problem);
+
+/// Read a graph and its problem from a DIMACS shortest path or maximum flow file.
+///
+/// # Examples
+///
+/// ```
+/// use safe_graph::io::dimacs::{self, Problem};
+/// use safe_graph::Graph;
+///
+/// let data = "c example\np sp 3 2\na 1 2 10\na 2 3 20\n";
+/// let (graph, problem): (Graph<u32, u64>, _) = dimacs::read(data.as_bytes()).unwrap();
+///
+/// assert_eq!(problem, Problem::ShortestPath);
+/// assert_eq!(graph.edge_weight(0, 1), Some(&10));
+/// ```
+pub fn read<E, Ty, S, R>(reader: R) -> Result<Instance<E, Ty, S>, Error>
+where
+    E: FromStr,
+    Ty: EdgeType,
+    S: BuildHasher + Default,
+    R: BufRead,
+{
+    let mut graph: Option<Graph<u32, E, Ty, S>> = None;
+    let mut nodes = 0;
+    let mut max_flow = false;
+    let mut source = None;
+    let mut sink = None;
+    let mut expected = 0;
+    let mut arcs = 0;
+    let mut number = 0;
+
+    for line in reader.lines() {
+        let line = line?;
+        number += 1;
+        let fields: Vec<&str> = line.split_whitespace().collect();
+
+        match (fields.first().cloned(), graph.as_mut()) {
+            (None, _) | (Some("c"), _) => continue,
+            (Some("p"), None) => {
+                if fields.len() != 4 {
+                    return Err(Error::parse(number, "expected `p problem nodes arcs`"));
+                }
+                max_flow = match fields[1] {
+                    "sp" => false,
+                    "max" => true,
+                    other => {
+                        return Err(Error::parse(
+                            number,
+                            format!("unsupported problem `{}`", other),
+                        ))
+                    }
+                };
+                nodes = parse_field(fields[2], number)?;
+                expected = parse_field(fields[3], number)?;
+                if nodes == 0 && expected > 0 {
+                    return Err(Error::parse(number, "arcs declared without nodes"));
+                }
+
+                // Don't trust the counts for preallocation, the data could be corrupted.
+                graph = Some(Graph::with_capacity_default(
+                    nodes.min(1 << 16) as usize,
+                    expected.min(1 << 16),
+                ));
+            }
+            (Some("n"), Some(g)) if max_flow => {
+                if fields.len() != 3 {
+                    return Err(Error::parse(number, "expected `n id s|t`"));
+                }
+                let n = g.add_node(id_to_node(fields[1], nodes, number)?);
+                match fields[2] {
+                    "s" => source = Some(n),
+                    "t" => sink = Some(n),
+                    other => {
+                        return Err(Error::parse(
+                            number,
+                            format!("unknown node designator `{}`", other),
+                        ))
+                    }
+                }
+            }
+            (Some("a"), Some(g)) => {
+                if fields.len() != 4 {
+                    return Err(Error::parse(number, "expected `a source target weight`"));
+                }
+                let a = id_to_node(fields[1], nodes, number)?;
+                let b = id_to_node(fields[2], nodes, number)?;
+                let weight = parse_field(fields[3], number)?;
+
+                g.add_edge(a, b, weight);
+                arcs += 1;
+            }
+            (Some(other), _) => {
+                return Err(Error::parse(number, format!("unexpected line `{}`", other)))
+            }
+        }
+    }
+
+    let graph = match graph {
+        Some(g) => g,
+        None => return Err(Error::parse(number, "missing problem line")),
+    };
+    if arcs != expected {
+        return Err(Error::parse(
+            number,
+            format!("expected {} arcs, found {}", expected, arcs),
+        ));
+    }
+
+    let problem = if max_flow {
+        match (source, sink) {
+            (Some(source), Some(sink)) => Problem::MaxFlow { source, sink },
+            _ => return Err(Error::parse(number, "missing source or sink node")),
+        }
+    } else {
+        Problem::ShortestPath
+    };
+
+    Ok((graph, problem))
+}
+
+/// Convert a 1-based DIMACS node id to a node, checking the number of nodes.
+fn id_to_node(field: &str, nodes: u32, line: usize) -> Result<u32, Error> {
+    let id: u32 = parse_field(field, line)?;
+    if id == 0 || id > nodes {
+        return Err(Error::parse(line, format!("node `{}` out of bounds", id)));
+    }
+
+    Ok(id - 1)
+}
+
+/// Write the graph as a DIMACS file of the given problem.
+///
+/// Return `Error::Format` if node `u32::MAX` is part of the graph or the problem, as its
+/// 1-based id doesn't fit into `u32`.
+///
+/// Wrap the writer into a `BufWriter` when writing to a file.
+pub fn write<E, Ty, S, W>(
+    graph: &Graph<u32, E, Ty, S>,
+    mut writer: W,
+    problem: Problem,
+) -> Result<(), Error>
+where
+    E: Display,
+    Ty: EdgeType,
+    S: BuildHasher,
+    W: Write,
+{
+    let designated = match problem {
+        Problem::ShortestPath => false,
+        Problem::MaxFlow { source, sink } => source == u32::MAX || sink == u32::MAX,
+    };
+    if designated || graph.contains_node(u32::MAX) {
+        return Err(Error::Format(format!(
+            "node `{}` has no DIMACS id",
+            u32::MAX
+        )));
+    }
+
+    let nodes = graph.nodes().max().map_or(0, |n| n + 1);
+    let arcs = if Ty::is_directed() {
+        graph.edge_count()
+    } else {
+        // Every edge but a self loop makes a pair of arcs.
+        2 * graph.edge_count() - graph.all_edges().filter(|&(a, b, _)| a == b).count()
+    };
+
+    match problem {
+        Problem::ShortestPath => writeln!(writer, "p sp {} {}", nodes, arcs)?,
+        Problem::MaxFlow { source, sink } => {
+            writeln!(writer, "p max {} {}", nodes, arcs)?;
+            writeln!(writer, "n {} s", source + 1)?;
+            writeln!(writer, "n {} t", sink + 1)?;
+        }
+    }
+
+    for (a, b, weight) in graph.all_edges() {
+        writeln!(writer, "a {} {} {}", a + 1, b + 1, weight)?;
+
+        if !Ty::is_directed() && a != b {
+            writeln!(writer, "a {} {} {}", b + 1, a + 1, weight)?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{Graph, Undirected};
+    use crate::io::dimacs::{self, Problem};
+    use crate::io::Error;
+
+    #[test]
+    fn read_shortest_path() {
+        let data = "c comment\n\np sp 4 3\na 1 2 10\na 2 3 20\nc another comment\na 3 1 30\n";
+        let (graph, problem): (Graph<u32, u32>, _) = dimacs::read(data.as_bytes()).unwrap();
+
+        assert_eq!(problem, Problem::ShortestPath);
+        // Node `3` without any arc is not part of the graph.
+        assert_eq!(graph.node_count(), 3);
+        assert!(!graph.contains_node(3));
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.edge_weight(0, 1), Some(&10));
+        assert_eq!(graph.edge_weight(2, 0), Some(&30));
+    }
+
+    #[test]
+    fn read_max_flow() {
+        let data = "p max 3 2\nn 1 s\nn 3 t\na 1 2 5\na 2 3 4\n";
+        let (graph, problem): (Graph<u32, u32>, _) = dimacs::read(data.as_bytes()).unwrap();
+
+        assert_eq!(problem, Problem::MaxFlow { source: 0, sink: 2 });
+        assert_eq!(graph.edge_weight(1, 2), Some(&4));
+    }
+
+    #[test]
+    fn read_errors() {
+        let cases = [
+            "",
+            "a 1 2 3\n",
+            "p tw 2 1\n",
+            "p sp 2\n",
+            "p sp 2 1\na 1 3 1\n",
+            "p sp 2 1\na 1 2\n",
+            "p sp 2 1\na 1 2 x\n",
+            "p sp 2 2\na 1 2 1\n",
+            "p sp 2 1\nn 1 s\na 1 2 1\n",
+            "p max 2 1\nn 1 s\na 1 2 1\n",
+            "p max 2 1\nn 1 x\na 1 2 1\n",
+            "p sp 0 1\n",
+        ];
+
+        for data in cases.iter() {
+            let result: Result<(Graph<u32, u32>, _), Error> = dimacs::read(data.as_bytes());
+            assert!(result.is_err(), "Expected an error for `{}`.", data);
+        }
+    }
+
+    #[test]
+    fn read_huge_header() {
+        // Test the declared node count is not materialised.
+        let data = "p sp 4294967295 1\na 4294967295 1 7\n";
+        let (graph, _): (Graph<u32, u32>, _) = dimacs::read(data.as_bytes()).unwrap();
+
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.edge_weight(u32::MAX - 1, 0), Some(&7));
+    }
+
+    #[test]
+    fn write_shortest_path() {
+        let graph = Graph::<u32, u32>::from_edges(&[(0, 1, 10), (1, 2, 20)]);
+        let mut out = Vec::new();
+
+        dimacs::write(&graph, &mut out, Problem::ShortestPath).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "p sp 3 2\na 1 2 10\na 2 3 20\n"
+        );
+    }
+
+    #[test]
+    fn write_max_node() {
+        let graph = Graph::<u32, u32>::from_edges(&[(u32::MAX, 0, 1)]);
+        let problem = Problem::MaxFlow {
+            source: u32::MAX,
+            sink: 0,
+        };
+        let mut out = Vec::new();
+
+        match dimacs::write(&graph, &mut out, problem) {
+            Err(Error::Format(_)) => {}
+            _ => panic!("Expected a format error."),
+        }
+        assert!(out.is_empty());
+
+        // Test the source and sink are checked as well.
+        let graph = Graph::<u32, u32>::from_edges(&[(0, 1, 1)]);
+        let result = dimacs::write(&graph, &mut out, problem);
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn write_max_flow_undirected() {
+        let graph = Graph::<u32, u32, Undirected>::from_edges(&[(0, 1, 5), (1, 1, 2)]);
+        let problem = Problem::MaxFlow { source: 0, sink: 1 };
+        let mut out = Vec::new();
+
+        dimacs::write(&graph, &mut out, problem).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out.clone()).unwrap(),
+            "p max 2 3\nn 1 s\nn 2 t\na 1 2 5\na 2 1 5\na 2 2 2\n"
+        );
+
+        // Read it back.
+        let (read, read_problem): (Graph<u32, u32, Undirected>, _) =
+            dimacs::read(&out[..]).unwrap();
+
+        assert_eq!(read_problem, problem);
+        assert_eq!(read.edge_count(), 2);
+        assert_eq!(read.edge_weight(1, 0), Some(&5));
+    }
+}
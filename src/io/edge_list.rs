@@ -0,0 +1,325 @@
+//! Plain text edge list format.
+//!
+//! Every line holds a single edge as `source target [weight]`, the fields being separated by
+//! a configurable `Delimiter`. Blank lines and lines starting with the comment character are
+//! skipped, which makes the format compatible with e.g. SNAP datasets and simple CSV files.
+//!
+//! ```text
+//! # Directed graph: 3 nodes, 2 edges
+//! 1 2 0.5
+//! 2 3 1.5
+//! ```
+
+use crate::edge::EdgeType;
+use crate::graph::Graph;
+use crate::io::{parse_field, Delimiter, Error, Fields, LineReader};
+use crate::node::NodeTrait;
+use std::fmt::Display;
+use std::hash::BuildHasher;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+/// Configuration of the edge list format.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct EdgeListFormat {
+    delimiter: Delimiter,
+    comment: char,
+}
+
+impl EdgeListFormat {
+    /// Create a new whitespace separated format with `#` comments.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new comma separated format with `#` comments.
+    pub fn csv() -> Self {
+        Self::new().delimiter(Delimiter::Char(','))
+    }
+
+    /// Set the field delimiter.
+    pub fn delimiter(mut self, delimiter: Delimiter) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Set the character starting comment lines.
+    pub fn comment(mut self, comment: char) -> Self {
+        self.comment = comment;
+        self
+    }
+}
+
+impl Default for EdgeListFormat {
+    fn default() -> Self {
+        Self {
+            delimiter: Delimiter::Whitespace,
+            comment: '#',
+        }
+    }
+}
+
+/// Parser of the fields of a single line into an edge.
+type EdgeParser<N, E> = fn(&mut Fields<'_>, usize) -> Result<(N, N, E), Error>;
+
+/// Parse `source target [weight]`, filling a missing weight with the default value.
+fn weighted_edge<N, E>(fields: &mut Fields<'_>, line: usize) -> Result<(N, N, E), Error>
+where
+    N: FromStr,
+    E: FromStr + Default,
+{
+    let a = next_node(fields, line)?;
+    let b = next_node(fields, line)?;
+    let weight = match fields.next() {
+        Some(field) if !field.is_empty() => parse_field(field, line)?,
+        _ => E::default(),
+    };
+
+    if fields.next().is_some() {
+        return Err(Error::parse(line, "too many fields"));
+    }
+
+    Ok((a, b, weight))
+}
+
+/// Parse `source target`, ignoring any further fields.
+fn unweighted_edge<N, E>(fields: &mut Fields<'_>, line: usize) -> Result<(N, N, E), Error>
+where
+    N: FromStr,
+    E: Default,
+{
+    let a = next_node(fields, line)?;
+    let b = next_node(fields, line)?;
+
+    Ok((a, b, E::default()))
+}
+
+/// Parse the next node field.
+fn next_node<N>(fields: &mut Fields<'_>, line: usize) -> Result<N, Error>
+where
+    N: FromStr,
+{
+    match fields.next() {
+        Some(field) => parse_field(field, line),
+        None => Err(Error::parse(line, "missing node")),
+    }
+}
+
+/// Streaming reader of an edge list.
+///
+/// Iterator element type is `Result<(N, N, E), Error>`.
+pub struct EdgeListReader<R, N, E> {
+    lines: LineReader<R>,
+    delimiter: Delimiter,
+    parse: EdgeParser<N, E>,
+}
+
+impl<R, N, E> Iterator for EdgeListReader<R, N, E>
+where
+    R: BufRead,
+{
+    type Item = Result<(N, N, E), Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (number, line) = match self.lines.next_line()? {
+            Ok(line) => line,
+            Err(err) => return Some(Err(err)),
+        };
+        let mut fields = self.delimiter.split(line);
+
+        Some((self.parse)(&mut fields, number))
+    }
+}
+
+/// Read weighted edges `(source, target, weight)` from an edge list.
+///
+/// # Examples
+///
+/// ```
+/// use safe_graph::io::edge_list::{self, EdgeListFormat};
+/// use safe_graph::io::try_extend;
+/// use safe_graph::Graph;
+///
+/// let data = "# comment\n1 2 0.5\n2 3\n";
+/// let mut graph: Graph<u32, f32> = Graph::new();
+///
+/// try_extend(&mut graph, edge_list::read_edges(data.as_bytes(), EdgeListFormat::new())).unwrap();
+///
+/// assert_eq!(graph.edge_weight(1, 2), Some(&0.5));
+/// assert_eq!(graph.edge_weight(2, 3), Some(&0.0));
+/// ```
+pub fn read_edges<N, E, R>(reader: R, format: EdgeListFormat) -> EdgeListReader<R, N, E>
+where
+    N: FromStr,
+    E: FromStr + Default,
+    R: BufRead,
+{
+    EdgeListReader {
+        lines: LineReader::new(reader, format.comment),
+        delimiter: format.delimiter,
+        parse: weighted_edge::<N, E>,
+    }
+}
+
+/// Read edges `(source, target)` from an edge list, ignoring any weights and using
+/// default edge weights instead.
+pub fn read_pairs<N, E, R>(reader: R, format: EdgeListFormat) -> EdgeListReader<R, N, E>
+where
+    N: FromStr,
+    E: Default,
+    R: BufRead,
+{
+    EdgeListReader {
+        lines: LineReader::new(reader, format.comment),
+        delimiter: format.delimiter,
+        parse: unweighted_edge::<N, E>,
+    }
+}
+
+/// Write all edges of the graph together with their weights, one edge per line.
+///
+/// Wrap the writer into a `BufWriter` when writing to a file.
+pub fn write_edges<N, E, Ty, S, W>(
+    graph: &Graph<N, E, Ty, S>,
+    mut writer: W,
+    format: EdgeListFormat,
+) -> Result<(), Error>
+where
+    N: NodeTrait + Display,
+    E: Display,
+    Ty: EdgeType,
+    S: BuildHasher,
+    W: Write,
+{
+    let delimiter = format.delimiter;
+    for (a, b, weight) in graph.all_edges() {
+        writeln!(writer, "{}{}{}{}{}", a, delimiter, b, delimiter, weight)?;
+    }
+
+    Ok(())
+}
+
+/// Write all edges of the graph without their weights, one edge per line.
+///
+/// Wrap the writer into a `BufWriter` when writing to a file.
+pub fn write_pairs<N, E, Ty, S, W>(
+    graph: &Graph<N, E, Ty, S>,
+    mut writer: W,
+    format: EdgeListFormat,
+) -> Result<(), Error>
+where
+    N: NodeTrait + Display,
+    Ty: EdgeType,
+    S: BuildHasher,
+    W: Write,
+{
+    let delimiter = format.delimiter;
+    for (a, b, _) in graph.all_edges() {
+        writeln!(writer, "{}{}{}", a, delimiter, b)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{Graph, Undirected};
+    use crate::io::edge_list::{self, EdgeListFormat};
+    use crate::io::{try_extend, Delimiter, Error};
+
+    #[test]
+    fn format_builder() {
+        let format = EdgeListFormat::new()
+            .delimiter(Delimiter::Char(';'))
+            .comment('%');
+
+        assert_eq!(format.delimiter, Delimiter::Char(';'));
+        assert_eq!(format.comment, '%');
+        assert_eq!(EdgeListFormat::csv().delimiter, Delimiter::Char(','));
+    }
+
+    #[test]
+    fn read_edges() {
+        let data = "# Nodes: 3 Edges: 3\n\n1 2 1.5\n2\t3 2.5\n  # indented comment\n3 1\n";
+        let edges: Vec<(u32, u32, f32)> =
+            edge_list::read_edges(data.as_bytes(), EdgeListFormat::new())
+                .collect::<Result<_, _>>()
+                .unwrap();
+
+        assert_eq!(edges, vec![(1, 2, 1.5), (2, 3, 2.5), (3, 1, 0.0)]);
+    }
+
+    #[test]
+    fn read_edges_csv() {
+        let data = "1,2,1.5\n2, 3 ,2.5\n";
+        let edges: Vec<(u32, u32, f32)> =
+            edge_list::read_edges(data.as_bytes(), EdgeListFormat::csv())
+                .collect::<Result<_, _>>()
+                .unwrap();
+
+        assert_eq!(edges, vec![(1, 2, 1.5), (2, 3, 2.5)]);
+    }
+
+    #[test]
+    fn read_edges_errors() {
+        let data = "1 2\n1\n1 x\n1 2 3 4\n";
+        let lines: Vec<usize> =
+            edge_list::read_edges::<u32, u32, _>(data.as_bytes(), EdgeListFormat::new())
+                .filter_map(|edge| match edge {
+                    Err(Error::Parse { line, .. }) => Some(line),
+                    _ => None,
+                })
+                .collect();
+
+        assert_eq!(lines, vec![2, 3, 4]);
+    }
+
+    #[test]
+    fn read_pairs() {
+        let data = "% comment\n1 2 0.5\n2 3\n";
+        let format = EdgeListFormat::new().comment('%');
+        let mut graph: Graph<u32, ()> = Graph::new();
+
+        try_extend(&mut graph, edge_list::read_pairs(data.as_bytes(), format)).unwrap();
+
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(graph.edge_count(), 2);
+        assert!(graph.contains_edge(1, 2));
+        assert!(graph.contains_edge(2, 3));
+    }
+
+    #[test]
+    fn write_edges() {
+        let graph = Graph::<u32, f32>::from_edges(&[(1, 2, 1.5), (2, 3, 2.0)]);
+        let mut out = Vec::new();
+
+        edge_list::write_edges(&graph, &mut out, EdgeListFormat::csv()).unwrap();
+
+        assert_eq!(String::from_utf8(out).unwrap(), "1,2,1.5\n2,3,2\n");
+    }
+
+    #[test]
+    fn write_pairs() {
+        let graph = Graph::<u32, ()>::from_edges(&[(1, 2), (2, 3)]);
+        let mut out = Vec::new();
+
+        edge_list::write_pairs(&graph, &mut out, EdgeListFormat::new()).unwrap();
+
+        assert_eq!(String::from_utf8(out).unwrap(), "1 2\n2 3\n");
+    }
+
+    #[test]
+    fn round_trip_undirected() {
+        let graph = Graph::<u32, u32, Undirected>::from_edges(&[(2, 1, 7), (3, 2, 8)]);
+        let mut out = Vec::new();
+        edge_list::write_edges(&graph, &mut out, EdgeListFormat::new()).unwrap();
+
+        let mut read: Graph<u32, u32, Undirected> = Graph::new();
+        let reader = edge_list::read_edges(&out[..], EdgeListFormat::new());
+        try_extend(&mut read, reader).unwrap();
+
+        assert_eq!(read.edge_count(), 2);
+        assert_eq!(read.edge_weight(1, 2), Some(&7));
+        assert_eq!(read.edge_weight(2, 3), Some(&8));
+    }
+}
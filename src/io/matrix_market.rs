@@ -0,0 +1,439 @@
+//! Matrix Market coordinate format.
+//!
+//! The graph is stored as a sparse adjacency matrix, one non-zero entry per edge. A `general`
+//! matrix maps to a `Directed` graph, while a `symmetric` matrix (storing the lower triangle
+//! only) maps to an `Undirected` graph.
+//!
+//! Matrix Market indices are 1-based, node `n` of the graph is stored at index `n + 1`.
+//!
+//! The matrix dimension only bounds the indices. Nodes are added as they are referenced by the
+//! entries, so nodes without any edge are not part of the read graph.
+//!
+//! ```text
+//! %%MatrixMarket matrix coordinate real general
+//! % 3 nodes, 2 edges
+//! 3 3 2
+//! 1 2 0.5
+//! 2 3 1.5
+//! ```
+
+use crate::edge::EdgeType;
+use crate::graph::Graph;
+use crate::io::{parse_field, Error};
+use std::fmt::Display;
+use std::hash::BuildHasher;
+use std::io::{BufRead, Write};
+use std::str::FromStr;
+
+/// Header line prefix of a Matrix Market file.
+const BANNER: &str = "%%MatrixMarket";
+
+/// Type of the matrix entries.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Field {
+    /// Floating point entries.
+    Real,
+    /// Integer entries.
+    Integer,
+    /// No entries, only the structure of the matrix.
+    Pattern,
+}
+
+impl Field {
+    fn name(self) -> &'static str {
+        match self {
+            Field::Real => "real",
+            Field::Integer => "integer",
+            Field::Pattern => "pattern",
+        }
+    }
+}
+
+impl FromStr for Field {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "real" => Ok(Field::Real),
+            "integer" => Ok(Field::Integer),
+            "pattern" => Ok(Field::Pattern),
+            _ => Err(()),
+        }
+    }
+}
+
+/// Matrix symmetry corresponding to the edge type `Ty`.
+fn symmetry<Ty: EdgeType>() -> &'static str {
+    if Ty::is_directed() {
+        "general"
+    } else {
+        "symmetric"
+    }
+}
+
+/// Read a graph from the Matrix Market coordinate format, parsing the edge weights.
+///
+/// Edge weights of a `pattern` matrix are filled with the default value.
+///
+/// # Examples
+///
+/// ```
+/// use safe_graph::io::matrix_market;
+/// use safe_graph::Graph;
+///
+/// let data = "%%MatrixMarket matrix coordinate real general\n3 3 2\n1 2 0.5\n2 3 1.5\n";
+/// let graph: Graph<u32, f64> = matrix_market::read(data.as_bytes()).unwrap();
+///
+/// assert_eq!(graph.node_count(), 3);
+/// assert_eq!(graph.edge_weight(0, 1), Some(&0.5));
+/// ```
+pub fn read<E, Ty, S, R>(reader: R) -> Result<Graph<u32, E, Ty, S>, Error>
+where
+    E: FromStr + Default,
+    Ty: EdgeType,
+    S: BuildHasher + Default,
+    R: BufRead,
+{
+    read_with(reader, |field, line| match field {
+        Some(field) => parse_field(field, line),
+        None => Ok(E::default()),
+    })
+}
+
+/// Read a graph from the Matrix Market coordinate format, ignoring the matrix entries and
+/// using default edge weights instead.
+pub fn read_unweighted<E, Ty, S, R>(reader: R) -> Result<Graph<u32, E, Ty, S>, Error>
+where
+    E: Default,
+    Ty: EdgeType,
+    S: BuildHasher + Default,
+    R: BufRead,
+{
+    read_with(reader, |_, _| Ok(E::default()))
+}
+
+fn read_with<E, Ty, S, R, F>(reader: R, mut weight: F) -> Result<Graph<u32, E, Ty, S>, Error>
+where
+    Ty: EdgeType,
+    S: BuildHasher + Default,
+    R: BufRead,
+    F: FnMut(Option<&str>, usize) -> Result<E, Error>,
+{
+    let mut lines = reader.lines();
+
+    // Parse the banner, e.g. `%%MatrixMarket matrix coordinate real general`.
+    let banner = match lines.next() {
+        Some(line) => line?,
+        None => return Err(Error::parse(1, "missing header")),
+    };
+    let banner: Vec<&str> = banner.split_whitespace().collect();
+    if banner.len() != 5
+        || banner[0] != BANNER
+        || !banner[1].eq_ignore_ascii_case("matrix")
+        || !banner[2].eq_ignore_ascii_case("coordinate")
+    {
+        return Err(Error::parse(1, "expected a coordinate matrix header"));
+    }
+    let field: Field = banner[3]
+        .parse()
+        .map_err(|_| Error::parse(1, format!("unsupported field `{}`", banner[3])))?;
+    if !banner[4].eq_ignore_ascii_case(symmetry::<Ty>()) {
+        return Err(Error::parse(
+            1,
+            format!(
+                "expected `{}` symmetry, found `{}`",
+                symmetry::<Ty>(),
+                banner[4]
+            ),
+        ));
+    }
+
+    let mut graph: Option<Graph<u32, E, Ty, S>> = None;
+    let mut nodes = 0;
+    let mut expected = 0;
+    let mut entries = 0;
+    let mut number = 1;
+
+    for line in lines {
+        let line = line?;
+        number += 1;
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('%') {
+            continue;
+        }
+
+        let fields: Vec<&str> = trimmed.split_whitespace().collect();
+
+        match graph {
+            // The size line `rows columns entries` comes first.
+            None => {
+                if fields.len() != 3 {
+                    return Err(Error::parse(number, "expected `rows columns entries`"));
+                }
+                let rows: u32 = parse_field(fields[0], number)?;
+                let columns: u32 = parse_field(fields[1], number)?;
+                expected = parse_field(fields[2], number)?;
+                if rows != columns {
+                    return Err(Error::parse(number, "adjacency matrix is not square"));
+                }
+                if expected as u64 > u64::from(rows) * u64::from(rows) {
+                    return Err(Error::parse(number, "more entries than matrix cells"));
+                }
+
+                // Don't trust the counts for preallocation, the data could be corrupted.
+                nodes = rows;
+                graph = Some(Graph::with_capacity_default(
+                    nodes.min(1 << 16) as usize,
+                    expected.min(1 << 16),
+                ));
+            }
+            Some(ref mut g) => {
+                let values = if field == Field::Pattern { 2 } else { 3 };
+                if fields.len() != values {
+                    return Err(Error::parse(number, "unexpected number of fields"));
+                }
+
+                let a = index_to_node(fields[0], nodes, number)?;
+                let b = index_to_node(fields[1], nodes, number)?;
+                let w = weight(fields.get(2).cloned(), number)?;
+
+                if !Ty::is_directed() && a < b {
+                    return Err(Error::parse(number, "symmetric entry above the diagonal"));
+                }
+                g.add_edge(a, b, w);
+                entries += 1;
+            }
+        }
+    }
+
+    match graph {
+        Some(g) if entries == expected => Ok(g),
+        Some(_) => Err(Error::parse(
+            number,
+            format!("expected {} entries, found {}", expected, entries),
+        )),
+        None => Err(Error::parse(number, "missing size line")),
+    }
+}
+
+/// Convert a 1-based matrix index to a node, checking the matrix dimension.
+fn index_to_node(field: &str, nodes: u32, line: usize) -> Result<u32, Error> {
+    let index: u32 = parse_field(field, line)?;
+    if index == 0 || index > nodes {
+        return Err(Error::parse(
+            line,
+            format!("index `{}` out of bounds", index),
+        ));
+    }
+
+    Ok(index - 1)
+}
+
+/// Write the graph in the Matrix Market coordinate format with the edge weights as entries.
+///
+/// Writing with `Field::Pattern` omits the edge weights. Return `Error::Format` if node
+/// `u32::MAX` is part of the graph, as its 1-based index doesn't fit into `u32`.
+pub fn write<E, Ty, S, W>(
+    graph: &Graph<u32, E, Ty, S>,
+    writer: W,
+    field: Field,
+) -> Result<(), Error>
+where
+    E: Display,
+    Ty: EdgeType,
+    S: BuildHasher,
+    W: Write,
+{
+    write_with(graph, writer, field, |writer, weight| {
+        write!(writer, " {}", weight)
+    })
+}
+
+/// Write the graph in the Matrix Market coordinate format as a `pattern` matrix.
+///
+/// Return `Error::Format` if node `u32::MAX` is part of the graph.
+pub fn write_unweighted<E, Ty, S, W>(graph: &Graph<u32, E, Ty, S>, writer: W) -> Result<(), Error>
+where
+    Ty: EdgeType,
+    S: BuildHasher,
+    W: Write,
+{
+    write_with(graph, writer, Field::Pattern, |_, _| Ok(()))
+}
+
+fn write_with<E, Ty, S, W, F>(
+    graph: &Graph<u32, E, Ty, S>,
+    mut writer: W,
+    field: Field,
+    mut weight: F,
+) -> Result<(), Error>
+where
+    Ty: EdgeType,
+    S: BuildHasher,
+    W: Write,
+    F: FnMut(&mut W, &E) -> std::io::Result<()>,
+{
+    if graph.contains_node(u32::MAX) {
+        return Err(Error::Format(format!(
+            "node `{}` has no Matrix Market index",
+            u32::MAX
+        )));
+    }
+
+    let dimension = graph.nodes().max().map_or(0, |n| n + 1);
+
+    writeln!(
+        writer,
+        "{} matrix coordinate {} {}",
+        BANNER,
+        field.name(),
+        symmetry::<Ty>()
+    )?;
+    writeln!(writer, "{} {} {}", dimension, dimension, graph.edge_count())?;
+
+    for (a, b, w) in graph.all_edges() {
+        // Symmetric matrices store the lower triangle, i.e. row >= column.
+        let (row, column) = if Ty::is_directed() || a >= b {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        write!(writer, "{} {}", row + 1, column + 1)?;
+        if field != Field::Pattern {
+            weight(&mut writer, w)?;
+        }
+        writeln!(writer)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{Directed, Graph, Undirected};
+    use crate::io::matrix_market::{self, Field};
+    use crate::io::Error;
+
+    #[test]
+    fn field_from_str() {
+        assert_eq!("real".parse(), Ok(Field::Real));
+        assert_eq!("Integer".parse(), Ok(Field::Integer));
+        assert_eq!("pattern".parse(), Ok(Field::Pattern));
+        assert_eq!("complex".parse::<Field>(), Err(()));
+    }
+
+    #[test]
+    fn read_general() {
+        let data = "%%MatrixMarket matrix coordinate integer general\n\
+                    % comment\n\
+                    4 4 3\n\
+                    1 2 5\n\
+                    2 1 6\n\
+                    3 3 7\n";
+        let graph: Graph<u32, i32, Directed> = matrix_market::read(data.as_bytes()).unwrap();
+
+        // Node `3` without any edge is not part of the graph.
+        assert_eq!(graph.node_count(), 3);
+        assert!(!graph.contains_node(3));
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.edge_weight(0, 1), Some(&5));
+        assert_eq!(graph.edge_weight(1, 0), Some(&6));
+        assert_eq!(graph.edge_weight(2, 2), Some(&7));
+    }
+
+    #[test]
+    fn read_symmetric_pattern() {
+        let data = "%%MatrixMarket matrix coordinate pattern symmetric\n3 3 2\n2 1\n3 2\n";
+        let graph: Graph<u32, (), Undirected> =
+            matrix_market::read_unweighted(data.as_bytes()).unwrap();
+
+        assert_eq!(graph.edge_count(), 2);
+        assert!(graph.contains_edge(0, 1));
+        assert!(graph.contains_edge(2, 1));
+    }
+
+    #[test]
+    fn read_errors() {
+        let cases = [
+            "",
+            "%%MatrixMarket matrix array real general\n",
+            "%%MatrixMarket matrix coordinate complex general\n",
+            "%%MatrixMarket matrix coordinate real symmetric\n",
+            "%%MatrixMarket matrix coordinate real general\n",
+            "%%MatrixMarket matrix coordinate real general\n2 2\n",
+            "%%MatrixMarket matrix coordinate real general\n2 2 1\n1 3 1.0\n",
+            "%%MatrixMarket matrix coordinate real general\n2 2 1\n1 2\n",
+            "%%MatrixMarket matrix coordinate real general\n2 2 2\n1 2 1.0\n",
+            "%%MatrixMarket matrix coordinate real general\n2 3 1\n1 2 1.0\n",
+            "%%MatrixMarket matrix coordinate real general\n2 2 5\n1 2 1.0\n",
+        ];
+
+        for data in cases.iter() {
+            let result: Result<Graph<u32, f32>, Error> = matrix_market::read(data.as_bytes());
+            assert!(result.is_err(), "Expected an error for `{}`.", data);
+        }
+
+        let data = "%%MatrixMarket matrix coordinate real symmetric\n2 2 1\n1 2 1.0\n";
+        let result: Result<Graph<u32, f32, Undirected>, Error> =
+            matrix_market::read(data.as_bytes());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn read_huge_header() {
+        // Test the declared dimension is not materialised.
+        let data = "%%MatrixMarket matrix coordinate pattern general\n\
+                    4294967295 4294967295 1\n\
+                    4294967295 1\n";
+        let graph: Graph<u32, (), Directed> =
+            matrix_market::read_unweighted(data.as_bytes()).unwrap();
+
+        assert_eq!(graph.node_count(), 2);
+        assert!(graph.contains_edge(u32::MAX - 1, 0));
+    }
+
+    #[test]
+    fn write() {
+        let graph = Graph::<u32, f32>::from_edges(&[(0, 1, 0.5), (2, 0, 1.5)]);
+        let mut out = Vec::new();
+
+        matrix_market::write(&graph, &mut out, Field::Real).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "%%MatrixMarket matrix coordinate real general\n3 3 2\n1 2 0.5\n3 1 1.5\n"
+        );
+    }
+
+    #[test]
+    fn write_max_node() {
+        let graph = Graph::<u32, (), Undirected>::from_edges(&[(u32::MAX, 0)]);
+        let mut out = Vec::new();
+
+        match matrix_market::write_unweighted(&graph, &mut out) {
+            Err(Error::Format(_)) => {}
+            _ => panic!("Expected a format error."),
+        }
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn write_unweighted_symmetric() {
+        let graph = Graph::<u32, (), Undirected>::from_edges(&[(0, 1), (2, 1)]);
+        let mut out = Vec::new();
+
+        matrix_market::write_unweighted(&graph, &mut out).unwrap();
+
+        assert_eq!(
+            String::from_utf8(out.clone()).unwrap(),
+            "%%MatrixMarket matrix coordinate pattern symmetric\n3 3 2\n2 1\n3 2\n"
+        );
+
+        // Read it back.
+        let read: Graph<u32, (), Undirected> = matrix_market::read_unweighted(&out[..]).unwrap();
+
+        assert_eq!(read.node_count(), 3);
+        assert!(read.contains_edge(0, 1));
+        assert!(read.contains_edge(1, 2));
+    }
+}
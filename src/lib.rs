@@ -1,8 +1,23 @@
 #[macro_use]
 mod macros;
+pub mod csr;
 pub mod edge;
+pub mod generators;
 pub mod graph;
+pub mod interned;
+pub mod io;
+pub mod matrix;
+pub mod multigraph;
 pub mod node;
+pub mod node_weighted;
+pub mod stable;
 mod traverse;
+pub mod visit;
 
+pub use crate::csr::Csr;
 pub use crate::graph::{Directed, Graph, NodeTrait, Undirected, UndirectedGraph};
+pub use crate::interned::InternedGraph;
+pub use crate::matrix::MatrixGraph;
+pub use crate::multigraph::MultiGraph;
+pub use crate::node_weighted::NodeWeightedGraph;
+pub use crate::stable::StableGraph;
@@ -0,0 +1,578 @@
+//! Dense adjacency matrix representations.
+
+use crate::edge::EdgeType;
+use crate::graph::{Directed, Graph};
+use crate::node::NodeTrait;
+use indexmap::IndexSet;
+use std::cmp;
+use std::fmt;
+use std::hash::BuildHasher;
+use std::marker::PhantomData;
+
+/// Dense adjacency matrix of a graph, stored in row-major order.
+///
+/// Entry `(i, j)` holds the weight of the edge from the node at index `i` to the node at index
+/// `j`, indices following the node order of the graph. The matrix of an undirected graph is
+/// symmetric.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdjacencyMatrix<E> {
+    size: usize,
+    entries: Vec<Option<E>>,
+}
+
+impl<E> AdjacencyMatrix<E> {
+    /// Return the number of rows, equal to the number of columns.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Return the weight of the edge from node index `i` to node index `j`, or `None` if
+    /// there is no such edge.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` or `j` is out of bounds.
+    pub fn get(&self, i: usize, j: usize) -> Option<&E> {
+        assert!(i < self.size && j < self.size, "Index out of bounds.");
+        self.entries[i * self.size + j].as_ref()
+    }
+
+    /// Return row `i` of the matrix.
+    pub fn row(&self, i: usize) -> &[Option<E>] {
+        &self.entries[i * self.size..(i + 1) * self.size]
+    }
+
+    /// Return all entries in row-major order.
+    pub fn as_slice(&self) -> &[Option<E>] {
+        &self.entries
+    }
+
+    /// Convert every entry by `f` into a dense row-major vector, e.g. for numeric code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use safe_graph::UndirectedGraph;
+    ///
+    /// let graph = UndirectedGraph::<_, f64>::from_edges(&[(1, 2, 0.5), (2, 3, 2.0)]);
+    /// let matrix = graph.to_adjacency_matrix().to_dense(|w| w.cloned().unwrap_or(0.0));
+    ///
+    /// assert_eq!(matrix, vec![0.0, 0.5, 0.0, 0.5, 0.0, 2.0, 0.0, 2.0, 0.0]);
+    /// ```
+    pub fn to_dense<T, F>(&self, mut f: F) -> Vec<T>
+    where
+        F: FnMut(Option<&E>) -> T,
+    {
+        self.entries.iter().map(|entry| f(entry.as_ref())).collect()
+    }
+}
+
+/// Dense adjacency matrix of a graph as a bit set, stored in row-major order.
+///
+/// Bit `(i, j)` is set if there is an edge from the node at index `i` to the node at index `j`,
+/// indices following the node order of the graph.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AdjacencyBitSet {
+    size: usize,
+    blocks: Vec<u64>,
+}
+
+impl AdjacencyBitSet {
+    /// Create a new bit set of `size` rows and columns, without edges.
+    pub fn new(size: usize) -> Self {
+        Self {
+            size,
+            blocks: vec![0; (size * size).div_ceil(64)],
+        }
+    }
+
+    /// Return the number of rows, equal to the number of columns.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Return `true` if there is an edge from node index `i` to node index `j`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` or `j` is out of bounds.
+    pub fn contains(&self, i: usize, j: usize) -> bool {
+        let bit = self.bit(i, j);
+        self.blocks[bit / 64] & (1 << (bit % 64)) != 0
+    }
+
+    /// Set the edge from node index `i` to node index `j`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` or `j` is out of bounds.
+    pub fn insert(&mut self, i: usize, j: usize) {
+        let bit = self.bit(i, j);
+        self.blocks[bit / 64] |= 1 << (bit % 64);
+    }
+
+    fn bit(&self, i: usize, j: usize) -> usize {
+        assert!(i < self.size && j < self.size, "Index out of bounds.");
+        i * self.size + j
+    }
+}
+
+impl<N, E, Ty, S> Graph<N, E, Ty, S>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    /// Return the dense adjacency matrix of the graph, rows and columns following the node
+    /// order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use safe_graph::Graph;
+    ///
+    /// let graph = Graph::<_, _>::from_edges(&[("a", "b", 1), ("b", "c", 2)]);
+    /// let matrix = graph.to_adjacency_matrix();
+    ///
+    /// assert_eq!(matrix.get(0, 1), Some(&1));
+    /// assert_eq!(matrix.get(1, 0), None);
+    /// assert_eq!(matrix.row(1), &[None, None, Some(2)]);
+    /// ```
+    pub fn to_adjacency_matrix(&self) -> AdjacencyMatrix<E>
+    where
+        E: Clone,
+    {
+        let size = self.node_count();
+        let mut entries = vec![None; size * size];
+
+        for (a, b, weight) in self.all_edges() {
+            let i = self
+                .node_index(a)
+                .expect("Edge node is not part of the graph.");
+            let j = self
+                .node_index(b)
+                .expect("Edge node is not part of the graph.");
+            entries[i * size + j] = Some(weight.clone());
+            if !Ty::is_directed() {
+                entries[j * size + i] = Some(weight.clone());
+            }
+        }
+
+        AdjacencyMatrix { size, entries }
+    }
+
+    /// Return the adjacency matrix of the graph as a bit set, rows and columns following the
+    /// node order. Edge weights are ignored.
+    pub fn to_adjacency_bitset(&self) -> AdjacencyBitSet {
+        let mut bitset = AdjacencyBitSet::new(self.node_count());
+
+        for (a, b, _) in self.all_edges() {
+            let i = self
+                .node_index(a)
+                .expect("Edge node is not part of the graph.");
+            let j = self
+                .node_index(b)
+                .expect("Edge node is not part of the graph.");
+            bitset.insert(i, j);
+            if !Ty::is_directed() {
+                bitset.insert(j, i);
+            }
+        }
+
+        bitset
+    }
+}
+
+/// `MatrixGraph<N, E, Ty>` is a graph backed by a dense adjacency matrix.
+///
+/// Nodes are indexed in insertion order and the edge weights are stored in a
+/// `capacity × capacity` matrix, giving **O(1)** edge insertion, lookup and removal at the
+/// cost of **O(|V|²)** space. Neighbor iteration scans a whole row. It suits small or dense
+/// graphs.
+///
+/// # Examples
+///
+/// ```
+/// use safe_graph::MatrixGraph;
+///
+/// let mut graph: MatrixGraph<char, u32> = MatrixGraph::new();
+/// graph.add_edge('a', 'b', 1);
+/// graph.add_edge('b', 'c', 2);
+///
+/// assert_eq!(graph.remove_edge('a', 'b'), Some(1));
+/// assert!(!graph.contains_edge('a', 'b'));
+/// assert_eq!(graph.neighbors('b').collect::<Vec<_>>(), vec!['c']);
+/// ```
+#[derive(Clone)]
+pub struct MatrixGraph<N, E, Ty = Directed> {
+    nodes: IndexSet<N>,
+    matrix: Vec<Option<E>>,
+    capacity: usize,
+    edge_count: usize,
+    ty: PhantomData<Ty>,
+}
+
+impl<N, E, Ty> fmt::Debug for MatrixGraph<N, E, Ty>
+where
+    N: NodeTrait,
+    E: fmt::Debug,
+    Ty: EdgeType,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MatrixGraph")
+            .field("nodes", &self.nodes)
+            .field("edges", &self.all_edges().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl<N, E, Ty> MatrixGraph<N, E, Ty>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+{
+    /// Create a new `MatrixGraph` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new `MatrixGraph` with room for `nodes` nodes.
+    pub fn with_capacity(nodes: usize) -> Self {
+        let mut matrix = Vec::new();
+        matrix.resize_with(nodes * nodes, || None);
+
+        Self {
+            nodes: IndexSet::with_capacity(nodes),
+            matrix,
+            capacity: nodes,
+            edge_count: 0,
+            ty: PhantomData,
+        }
+    }
+
+    /// Whether the graph has directed edges.
+    pub fn is_directed(&self) -> bool {
+        Ty::is_directed()
+    }
+
+    /// Return the number of nodes in the graph.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Return the number of edges in the graph.
+    pub fn edge_count(&self) -> usize {
+        self.edge_count
+    }
+
+    /// Add node `n` to the graph.
+    pub fn add_node(&mut self, n: N) -> N {
+        let (i, _) = self.nodes.insert_full(n);
+        if i >= self.capacity {
+            self.grow(i + 1);
+        }
+        n
+    }
+
+    /// Return `true` if the node is contained in the graph.
+    pub fn contains_node(&self, n: N) -> bool {
+        self.nodes.contains(&n)
+    }
+
+    /// Return the index of node `n`, or `None` if the node does not exist in the graph.
+    pub fn node_index(&self, n: N) -> Option<usize> {
+        self.nodes.get_index_of(&n)
+    }
+
+    /// Add an edge connecting `a` and `b` to the graph, with associated data `weight`, like
+    /// `Graph::add_edge`.
+    pub fn add_edge(&mut self, a: N, b: N, weight: E) -> Option<E> {
+        self.add_node(a);
+        self.add_node(b);
+
+        let i = self.position(a, b).expect("Nodes were just added.");
+        let old = self.matrix[i].replace(weight);
+        if old.is_none() {
+            self.edge_count += 1;
+        }
+        old
+    }
+
+    /// Remove the edge connecting `a` with `b` and return its weight, or `None` if the edge
+    /// does not exist. The nodes are kept.
+    pub fn remove_edge(&mut self, a: N, b: N) -> Option<E> {
+        let i = self.position(a, b)?;
+        let old = self.matrix[i].take();
+        if old.is_some() {
+            self.edge_count -= 1;
+        }
+        old
+    }
+
+    /// Return `true` if the edge connecting `a` with `b` is contained in the graph.
+    pub fn contains_edge(&self, a: N, b: N) -> bool {
+        self.edge_weight(a, b).is_some()
+    }
+
+    /// Return a reference to the edge weight connecting `a` with `b`, or
+    /// `None` if the edge does not exist in the graph.
+    pub fn edge_weight(&self, a: N, b: N) -> Option<&E> {
+        self.matrix[self.position(a, b)?].as_ref()
+    }
+
+    /// Return a mutable reference to the edge weight connecting `a` with `b`, or
+    /// `None` if the edge does not exist in the graph.
+    pub fn edge_weight_mut(&mut self, a: N, b: N) -> Option<&mut E> {
+        let i = self.position(a, b)?;
+        self.matrix[i].as_mut()
+    }
+
+    /// Return an iterator over the nodes of the graph, in index order.
+    ///
+    /// Iterator element type is `N`.
+    pub fn nodes(&self) -> MatrixNodes<'_, N> {
+        MatrixNodes {
+            iter: self.nodes.iter(),
+        }
+    }
+
+    /// Return an iterator of all nodes with an edge starting from `a`, in index order.
+    ///
+    /// - `Directed`: Outgoing edges from `a`.
+    /// - `Undirected`: All edges from or to `a`.
+    ///
+    /// Produces an empty iterator if the node doesn't exist.<br>
+    /// Iterator element type is `N`.
+    pub fn neighbors(&self, a: N) -> MatrixNeighbors<'_, N, E, Ty> {
+        MatrixNeighbors {
+            graph: self,
+            source: self.node_index(a),
+            next: 0,
+        }
+    }
+
+    /// Return an iterator over all edges of the graph with their weight, in index order.
+    ///
+    /// Iterator element type is `(N, N, &E)`.
+    pub fn all_edges(&self) -> impl Iterator<Item = (N, N, &E)> {
+        let n = self.nodes.len();
+
+        (0..n)
+            .flat_map(move |i| (0..n).map(move |j| (i, j)))
+            .filter(|&(i, j)| Ty::is_directed() || i <= j)
+            .filter_map(move |(i, j)| {
+                self.matrix[i * self.capacity + j]
+                    .as_ref()
+                    .map(|weight| (self.nodes[i], self.nodes[j], weight))
+            })
+    }
+
+    /// Return the position of edge `(a, b)` in the matrix, the upper triangle being used for
+    /// undirected edges.
+    fn position(&self, a: N, b: N) -> Option<usize> {
+        let i = self.nodes.get_index_of(&a)?;
+        let j = self.nodes.get_index_of(&b)?;
+        let (i, j) = if Ty::is_directed() {
+            (i, j)
+        } else {
+            (cmp::min(i, j), cmp::max(i, j))
+        };
+
+        Some(i * self.capacity + j)
+    }
+
+    /// Grow the matrix to at least `nodes` rows and columns, doubling the capacity.
+    fn grow(&mut self, nodes: usize) {
+        let capacity = cmp::max(nodes, cmp::max(4, 2 * self.capacity));
+
+        let mut matrix = Vec::new();
+        matrix.resize_with(capacity * capacity, || None);
+        for i in 0..self.capacity {
+            for j in 0..self.capacity {
+                matrix[i * capacity + j] = self.matrix[i * self.capacity + j].take();
+            }
+        }
+
+        self.matrix = matrix;
+        self.capacity = capacity;
+    }
+}
+
+/// Create a new empty `MatrixGraph`.
+impl<N, E, Ty> Default for MatrixGraph<N, E, Ty>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+{
+    fn default() -> Self {
+        MatrixGraph::with_capacity(0)
+    }
+}
+
+/// Convert a `Graph` into a `MatrixGraph`, keeping the node order.
+impl<N, E, Ty, S> From<Graph<N, E, Ty, S>> for MatrixGraph<N, E, Ty>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    fn from(graph: Graph<N, E, Ty, S>) -> Self {
+        let (nodes, edges) = graph.into_nodes_edges();
+
+        let mut matrix_graph = Self::with_capacity(nodes.len());
+        for n in nodes {
+            matrix_graph.add_node(n);
+        }
+        for (a, b, weight) in edges {
+            matrix_graph.add_edge(a, b, weight);
+        }
+        matrix_graph
+    }
+}
+
+/// Iterator over the nodes of a `MatrixGraph`.
+///
+/// Iterator element type is `N`.
+pub struct MatrixNodes<'a, N> {
+    iter: indexmap::set::Iter<'a, N>,
+}
+
+impl<'a, N> Iterator for MatrixNodes<'a, N>
+where
+    N: Copy,
+{
+    type Item = N;
+
+    fn next(&mut self) -> Option<N> {
+        self.iter.next().cloned()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Iterator over the neighbors of a node of a `MatrixGraph`.
+///
+/// Iterator element type is `N`.
+pub struct MatrixNeighbors<'a, N, E, Ty> {
+    graph: &'a MatrixGraph<N, E, Ty>,
+    source: Option<usize>,
+    next: usize,
+}
+
+impl<'a, N, E, Ty> Iterator for MatrixNeighbors<'a, N, E, Ty>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+{
+    type Item = N;
+
+    fn next(&mut self) -> Option<N> {
+        let i = self.source?;
+        let graph = self.graph;
+
+        while self.next < graph.nodes.len() {
+            let j = self.next;
+            self.next += 1;
+
+            let (row, column) = if Ty::is_directed() || i <= j {
+                (i, j)
+            } else {
+                (j, i)
+            };
+            if graph.matrix[row * graph.capacity + column].is_some() {
+                return Some(graph.nodes[j]);
+            }
+        }
+
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{Graph, Undirected};
+    use crate::matrix::{AdjacencyBitSet, MatrixGraph};
+
+    #[test]
+    fn to_adjacency_matrix() {
+        let mut graph = Graph::<u32, u32>::from_edges(&[(3, 1, 1), (1, 2, 2), (2, 2, 3)]);
+        graph.add_node(4);
+        let matrix = graph.to_adjacency_matrix();
+
+        assert_eq!(matrix.size(), 4);
+        assert_eq!(matrix.get(0, 1), Some(&1));
+        assert_eq!(matrix.get(1, 0), None);
+        assert_eq!(
+            matrix.to_dense(|w| w.cloned().unwrap_or(0)),
+            vec![0, 1, 0, 0, 0, 0, 2, 0, 0, 0, 3, 0, 0, 0, 0, 0]
+        );
+
+        let graph = Graph::<u32, u32, Undirected>::from_edges(&[(2, 1, 5)]);
+        let matrix = graph.to_adjacency_matrix();
+
+        assert_eq!(matrix.as_slice(), &[None, Some(5), Some(5), None]);
+    }
+
+    #[test]
+    fn to_adjacency_bitset() {
+        let graph = Graph::<u32, ()>::from_edges(&[(1, 2), (2, 3), (3, 3)]);
+        let bitset = graph.to_adjacency_bitset();
+
+        assert_eq!(bitset.size(), 3);
+        assert!(bitset.contains(0, 1));
+        assert!(!bitset.contains(1, 0));
+        assert!(bitset.contains(2, 2));
+
+        let graph = Graph::<u32, (), Undirected>::from_edges(&[(1, 2)]);
+        let bitset = graph.to_adjacency_bitset();
+
+        assert!(bitset.contains(0, 1));
+        assert!(bitset.contains(1, 0));
+        assert_eq!(AdjacencyBitSet::new(0).size(), 0);
+    }
+
+    #[test]
+    fn matrix_graph() {
+        let mut graph: MatrixGraph<u32, f32> = MatrixGraph::new();
+        for n in 0..10 {
+            graph.add_edge(n, (n + 1) % 10, n as f32);
+        }
+
+        assert_eq!(graph.node_count(), 10);
+        assert_eq!(graph.edge_count(), 10);
+        assert_eq!(graph.add_edge(0, 1, 5.0), Some(0.0));
+        assert_eq!(graph.edge_weight(0, 1), Some(&5.0));
+        assert_eq!(graph.edge_weight(1, 0), None);
+        assert_eq!(graph.neighbors(9).collect::<Vec<_>>(), vec![0]);
+
+        *graph.edge_weight_mut(9, 0).unwrap() = 1.0;
+        assert_eq!(graph.remove_edge(9, 0), Some(1.0));
+        assert_eq!(graph.remove_edge(9, 0), None);
+        assert_eq!(graph.remove_edge(9, 11), None);
+        assert_eq!(graph.edge_count(), 9);
+        assert_eq!(graph.neighbors(9).next(), None);
+        assert_eq!(graph.neighbors(11).next(), None);
+    }
+
+    #[test]
+    fn matrix_graph_undirected() {
+        let mut graph =
+            Graph::<u32, u32, Undirected>::from_edges(&[(3, 1, 1), (1, 2, 2), (2, 2, 3)]);
+        graph.add_node(4);
+        let mut graph = MatrixGraph::from(graph);
+
+        assert_eq!(graph.nodes().collect::<Vec<_>>(), vec![3, 1, 2, 4]);
+        assert_eq!(graph.edge_weight(1, 3), Some(&1));
+        assert_eq!(graph.neighbors(1).collect::<Vec<_>>(), vec![3, 2]);
+        assert_eq!(graph.neighbors(2).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(
+            graph.all_edges().collect::<Vec<_>>(),
+            vec![(3, 1, &1), (1, 2, &2), (2, 2, &3)]
+        );
+
+        assert_eq!(graph.remove_edge(2, 1), Some(2));
+        assert!(!graph.contains_edge(1, 2));
+    }
+}
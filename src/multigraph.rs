@@ -0,0 +1,554 @@
+//! Graph permitting parallel edges.
+
+use crate::edge::{
+    Direction::{Incoming, Outgoing},
+    EdgeType, IntoWeightedEdge,
+};
+use crate::graph::{Directed, Graph};
+use crate::node::{Adjacency, NodeTrait, Nodes};
+use crate::traverse::Neighbors;
+use indexmap::IndexMap;
+use std::collections::btree_map::{self, BTreeMap};
+use std::fmt;
+use std::hash::BuildHasher;
+use std::iter::FromIterator;
+use std::marker::PhantomData;
+use std::slice;
+
+/// Identifier of an edge of a `MultiGraph`.
+///
+/// Edge ids are assigned in increasing order and never reused.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EdgeId(usize);
+
+impl EdgeId {
+    /// Return the edge id as `usize`.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// `MultiGraph<N, E, Ty>` is a graph datastructure allowing several edges between the same
+/// pair of nodes.
+///
+/// Like `Graph`, nodes `N` are their own identifiers and `Ty` determines whether the edges
+/// are directed or undirected. Every edge is identified by an `EdgeId` returned by
+/// `add_edge`, edges are iterated in the order of their ids.
+///
+/// # Examples
+///
+/// ```
+/// use safe_graph::MultiGraph;
+///
+/// let mut routes: MultiGraph<&str, u32> = MultiGraph::new();
+/// let bus = routes.add_edge("a", "b", 12);
+/// routes.add_edge("a", "b", 7);
+///
+/// assert_eq!(routes.edge_count(), 2);
+/// assert_eq!(routes.edges_connecting("a", "b").map(|(_, &w)| w).min(), Some(7));
+///
+/// assert_eq!(routes.remove_edge(bus), Some(12));
+/// assert_eq!(routes.edge_count(), 1);
+/// ```
+#[derive(Clone)]
+pub struct MultiGraph<N, E, Ty = Directed> {
+    nodes: IndexMap<N, Adjacency<N>>,
+    connections: IndexMap<(N, N), Vec<EdgeId>>,
+    edges: BTreeMap<EdgeId, (N, N, E)>,
+    next_id: usize,
+    ty: PhantomData<Ty>,
+}
+
+impl<N, E, Ty> fmt::Debug for MultiGraph<N, E, Ty>
+where
+    N: NodeTrait,
+    E: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MultiGraph")
+            .field("nodes", &self.nodes.keys())
+            .field("edges", &self.edges)
+            .finish()
+    }
+}
+
+impl<N, E, Ty> MultiGraph<N, E, Ty>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+{
+    /// Create a new `MultiGraph` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new `MultiGraph` with estimated capacity.
+    pub fn with_capacity(nodes: usize, edges: usize) -> Self {
+        Self {
+            nodes: IndexMap::with_capacity(nodes),
+            connections: IndexMap::with_capacity(edges),
+            edges: BTreeMap::new(),
+            next_id: 0,
+            ty: PhantomData,
+        }
+    }
+
+    /// Whether the graph has directed edges.
+    pub fn is_directed(&self) -> bool {
+        Ty::is_directed()
+    }
+
+    /// Create a new `MultiGraph` from an iterable of edges.
+    ///
+    /// Nodes are inserted automatically to match the edges, every item becomes a new edge.
+    pub fn from_edges<I>(iterable: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: IntoWeightedEdge<E, NodeId = N>,
+    {
+        Self::from_iter(iterable)
+    }
+
+    /// Return the number of nodes in the graph.
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Return the number of edges in the graph, parallel edges included.
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Remove all nodes and edges.
+    pub fn clear(&mut self) {
+        self.nodes.clear();
+        self.connections.clear();
+        self.edges.clear();
+    }
+
+    /// Add node `n` to the graph.
+    pub fn add_node(&mut self, n: N) -> N {
+        self.nodes.entry(n).or_default();
+        n
+    }
+
+    /// Return `true` if the node is contained in the graph.
+    pub fn contains_node(&self, n: N) -> bool {
+        self.nodes.contains_key(&n)
+    }
+
+    /// Add a new edge connecting `a` and `b` to the graph, with associated data `weight`, and
+    /// return its id. For a directed graph, the edge is directed from `a` to `b`.
+    ///
+    /// Inserts nodes `a` and/or `b` if they aren't already part of the graph. Existing edges
+    /// connecting `a` and `b` are kept.
+    pub fn add_edge(&mut self, a: N, b: N, weight: E) -> EdgeId {
+        let id = EdgeId(self.next_id);
+        self.next_id += 1;
+
+        let parallel = self
+            .connections
+            .entry(Graph::<N, E, Ty>::edge_key(a, b))
+            .or_default();
+        if parallel.is_empty() {
+            // Insert in the adjacency lists if it's the first edge connecting the nodes.
+            self.nodes.entry(a).or_default().push(Outgoing, b);
+
+            // Undirected self loops are listed once.
+            if Ty::is_directed() {
+                self.nodes.entry(b).or_default().push(Incoming, a);
+            } else if a != b {
+                self.nodes.entry(b).or_default().push(Outgoing, a);
+            }
+        }
+        parallel.push(id);
+        self.edges.insert(id, (a, b, weight));
+
+        id
+    }
+
+    /// Remove the edge `id` from the graph and return its weight, or `None` if the edge does
+    /// not exist. The nodes are kept.
+    pub fn remove_edge(&mut self, id: EdgeId) -> Option<E> {
+        let (a, b, weight) = self.edges.remove(&id)?;
+        let key = Graph::<N, E, Ty>::edge_key(a, b);
+
+        let parallel = self
+            .connections
+            .get_mut(&key)
+            .expect("Edge is not part of its connection.");
+        parallel.retain(|&other| other != id);
+
+        if parallel.is_empty() {
+            self.connections.swap_remove(&key);
+
+            // Remove the adjacency entries of the last edge connecting the nodes.
+            let (first, second) = key;
+            if let Some(neighbors) = self.nodes.get_mut(&first) {
+                neighbors.remove(Outgoing, second);
+            }
+            if let Some(neighbors) = self.nodes.get_mut(&second) {
+                if Ty::is_directed() {
+                    neighbors.remove(Incoming, first);
+                } else if first != second {
+                    neighbors.remove(Outgoing, first);
+                }
+            }
+        }
+
+        Some(weight)
+    }
+
+    /// Return `true` if at least one edge connects `a` with `b`.
+    pub fn contains_edge(&self, a: N, b: N) -> bool {
+        self.connections
+            .contains_key(&Graph::<N, E, Ty>::edge_key(a, b))
+    }
+
+    /// Return the nodes `(a, b)` of edge `id` as they were added, or `None` if the edge does
+    /// not exist.
+    pub fn edge_endpoints(&self, id: EdgeId) -> Option<(N, N)> {
+        self.edges.get(&id).map(|&(a, b, _)| (a, b))
+    }
+
+    /// Return a reference to the weight of edge `id`, or `None` if the edge does not exist.
+    pub fn edge_weight(&self, id: EdgeId) -> Option<&E> {
+        self.edges.get(&id).map(|(_, _, weight)| weight)
+    }
+
+    /// Return a mutable reference to the weight of edge `id`, or `None` if the edge does not
+    /// exist.
+    pub fn edge_weight_mut(&mut self, id: EdgeId) -> Option<&mut E> {
+        self.edges.get_mut(&id).map(|(_, _, weight)| weight)
+    }
+
+    /// Return an iterator over all edges connecting `a` with `b`, in the order of their ids.
+    ///
+    /// - `Directed`: Edges from `a` to `b`.
+    /// - `Undirected`: Edges between `a` and `b`.
+    ///
+    /// Iterator element type is `(EdgeId, &E)`.
+    pub fn edges_connecting(&self, a: N, b: N) -> EdgesConnecting<'_, N, E> {
+        let ids = match self.connections.get(&Graph::<N, E, Ty>::edge_key(a, b)) {
+            Some(parallel) => parallel.iter(),
+            None => [].iter(),
+        };
+
+        EdgesConnecting {
+            ids,
+            edges: &self.edges,
+        }
+    }
+
+    /// Return an iterator over the nodes of the graph.
+    ///
+    /// Iterator element type is `N`.
+    pub fn nodes(&self) -> Nodes<'_, N> {
+        Nodes::new(self.nodes.keys().cloned())
+    }
+
+    /// Return an iterator of all nodes with an edge starting from `a`, each node being
+    /// yielded once whatever the number of parallel edges.
+    ///
+    /// - `Directed`: Outgoing edges from `a`.
+    /// - `Undirected`: All edges from or to `a`.
+    ///
+    /// Produces an empty iterator if the node doesn't exist.<br>
+    /// Iterator element type is `N`.
+    pub fn neighbors(&self, a: N) -> Neighbors<'_, N, Ty> {
+        let iter = match self.nodes.get(&a) {
+            Some(neigh) => neigh.get(Outgoing).iter(),
+            None => [].iter(),
+        };
+
+        Neighbors::new(iter, self.ty)
+    }
+
+    /// Return an iterator over all edges of the graph, in the order of their ids.
+    ///
+    /// Iterator element type is `(EdgeId, N, N, &E)`.
+    pub fn all_edges(&self) -> MultiEdges<'_, N, E> {
+        MultiEdges {
+            iter: self.edges.iter(),
+        }
+    }
+
+    /// Convert into a `Graph`, keeping all nodes.
+    ///
+    /// The weights of parallel edges are combined by `merge` in the order of the edge ids.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use safe_graph::MultiGraph;
+    ///
+    /// let routes = MultiGraph::<_, _>::from_edges(&[(1, 2, 12), (1, 2, 7), (2, 3, 5)]);
+    /// let fastest = routes.into_graph(|a, b| a.min(b));
+    ///
+    /// assert_eq!(fastest.edge_count(), 2);
+    /// assert_eq!(fastest.edge_weight(1, 2), Some(&7));
+    /// ```
+    pub fn into_graph<F>(self, mut merge: F) -> Graph<N, E, Ty>
+    where
+        F: FnMut(E, E) -> E,
+    {
+        let MultiGraph {
+            nodes,
+            connections,
+            mut edges,
+            ..
+        } = self;
+
+        let mut graph = Graph::with_capacity(nodes.len(), connections.len());
+        for &n in nodes.keys() {
+            graph.add_node(n);
+        }
+
+        let ids: Vec<EdgeId> = edges.keys().cloned().collect();
+        for id in ids {
+            // Parallel edges have already been merged into the edge with the lowest id.
+            if let Some((a, b, weight)) = edges.remove(&id) {
+                let parallel = &connections[&Graph::<N, E, Ty>::edge_key(a, b)];
+                let weight = parallel
+                    .iter()
+                    .filter(|&&other| other != id)
+                    .filter_map(|other| edges.remove(other))
+                    .fold(weight, |acc, (_, _, other)| merge(acc, other));
+
+                graph.add_edge(a, b, weight);
+            }
+        }
+
+        graph
+    }
+}
+
+/// Create a new empty `MultiGraph`.
+impl<N, E, Ty> Default for MultiGraph<N, E, Ty>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+{
+    fn default() -> Self {
+        MultiGraph::with_capacity(0, 0)
+    }
+}
+
+/// Convert a `Graph` into a `MultiGraph`, keeping nodes and edges order.
+impl<N, E, Ty, S> From<Graph<N, E, Ty, S>> for MultiGraph<N, E, Ty>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    fn from(graph: Graph<N, E, Ty, S>) -> Self {
+        let (nodes, edges) = graph.into_nodes_edges();
+
+        let mut multigraph = Self::with_capacity(nodes.len(), edges.len());
+        for n in nodes {
+            multigraph.add_node(n);
+        }
+        multigraph.extend(edges);
+        multigraph
+    }
+}
+
+/// Create a new `MultiGraph` from an iterable of edges.
+impl<N, E, Ty, Item> FromIterator<Item> for MultiGraph<N, E, Ty>
+where
+    Item: IntoWeightedEdge<E, NodeId = N>,
+    N: NodeTrait,
+    Ty: EdgeType,
+{
+    fn from_iter<I>(iterable: I) -> Self
+    where
+        I: IntoIterator<Item = Item>,
+    {
+        let mut g = Self::new();
+        g.extend(iterable);
+        g
+    }
+}
+
+/// Extend the graph from an iterable of edges, every item becoming a new edge.
+impl<N, E, Ty, Item> Extend<Item> for MultiGraph<N, E, Ty>
+where
+    Item: IntoWeightedEdge<E, NodeId = N>,
+    N: NodeTrait,
+    Ty: EdgeType,
+{
+    fn extend<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = Item>,
+    {
+        for elt in iterable {
+            let (source, target, weight) = elt.into_weighted_edge();
+            self.add_edge(source, target, weight);
+        }
+    }
+}
+
+/// Iterator over the edges connecting two nodes.
+///
+/// Iterator element type is `(EdgeId, &E)`.
+pub struct EdgesConnecting<'a, N, E> {
+    ids: slice::Iter<'a, EdgeId>,
+    edges: &'a BTreeMap<EdgeId, (N, N, E)>,
+}
+
+impl<'a, N, E> Iterator for EdgesConnecting<'a, N, E> {
+    type Item = (EdgeId, &'a E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let id = *self.ids.next()?;
+        self.edges.get(&id).map(|(_, _, weight)| (id, weight))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.ids.size_hint()
+    }
+}
+
+/// Iterator over all edges of a `MultiGraph`.
+///
+/// Iterator element type is `(EdgeId, N, N, &E)`.
+pub struct MultiEdges<'a, N, E> {
+    iter: btree_map::Iter<'a, EdgeId, (N, N, E)>,
+}
+
+impl<'a, N, E> Iterator for MultiEdges<'a, N, E>
+where
+    N: Copy,
+{
+    type Item = (EdgeId, N, N, &'a E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next()
+            .map(|(&id, &(a, b, ref weight))| (id, a, b, weight))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{Graph, Undirected};
+    use crate::multigraph::{EdgeId, MultiGraph};
+
+    #[test]
+    fn add_edge() {
+        let mut graph: MultiGraph<u32, f32> = MultiGraph::new();
+        let first = graph.add_edge(1, 2, 1.0);
+        let second = graph.add_edge(1, 2, 2.0);
+        let third = graph.add_edge(2, 1, 3.0);
+
+        assert_ne!(first, second);
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.edge_count(), 3);
+        assert_eq!(graph.edge_endpoints(third), Some((2, 1)));
+        assert_eq!(graph.edge_weight(second), Some(&2.0));
+
+        // Test neighbors are not repeated for parallel edges.
+        assert_eq!(graph.neighbors(1).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(
+            graph.edges_connecting(1, 2).collect::<Vec<_>>(),
+            vec![(first, &1.0), (second, &2.0)]
+        );
+        assert_eq!(
+            graph.edges_connecting(2, 1).collect::<Vec<_>>(),
+            vec![(third, &3.0)]
+        );
+    }
+
+    #[test]
+    fn undirected_edges_connecting() {
+        let graph =
+            MultiGraph::<u32, u32, Undirected>::from_edges(&[(1, 2, 1), (2, 1, 2), (2, 2, 3)]);
+
+        assert_eq!(graph.edges_connecting(2, 1).count(), 2);
+        assert_eq!(graph.neighbors(2).collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(
+            graph
+                .all_edges()
+                .map(|(_, a, b, &w)| (a, b, w))
+                .collect::<Vec<_>>(),
+            vec![(1, 2, 1), (2, 1, 2), (2, 2, 3)]
+        );
+    }
+
+    #[test]
+    fn remove_edge() {
+        let mut graph: MultiGraph<u32, f32> = MultiGraph::new();
+        let first = graph.add_edge(1, 2, 1.0);
+        let second = graph.add_edge(1, 2, 2.0);
+        let self_loop = graph.add_edge(2, 2, 3.0);
+
+        assert_eq!(graph.remove_edge(first), Some(1.0));
+        assert_eq!(graph.remove_edge(first), None);
+        assert!(graph.contains_edge(1, 2));
+        assert_eq!(graph.neighbors(1).collect::<Vec<_>>(), vec![2]);
+
+        // Test the adjacency is removed with the last parallel edge.
+        assert_eq!(graph.remove_edge(second), Some(2.0));
+        assert!(!graph.contains_edge(1, 2));
+        assert_eq!(graph.neighbors(1).next(), None);
+        assert_eq!(graph.neighbors(2).collect::<Vec<_>>(), vec![2]);
+
+        assert_eq!(graph.remove_edge(self_loop), Some(3.0));
+        assert_eq!(graph.neighbors(2).next(), None);
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.edge_count(), 0);
+
+        // Test ids are not reused.
+        assert_eq!(graph.add_edge(1, 2, 4.0), EdgeId(3));
+    }
+
+    #[test]
+    fn remove_edge_reciprocal() {
+        let mut graph: MultiGraph<u32, f32> = MultiGraph::new();
+        graph.add_edge(2, 1, 1.0);
+        let reverse = graph.add_edge(1, 2, 2.0);
+
+        // Test only the adjacency entries of the removed direction are removed.
+        assert_eq!(graph.remove_edge(reverse), Some(2.0));
+        assert_eq!(graph.neighbors(1).next(), None);
+        assert_eq!(graph.neighbors(2).collect::<Vec<_>>(), vec![1]);
+        assert!(graph.contains_edge(2, 1));
+    }
+
+    #[test]
+    fn edge_weight_mut() {
+        let mut graph: MultiGraph<u32, f32> = MultiGraph::new();
+        let id = graph.add_edge(1, 2, 1.0);
+
+        *graph.edge_weight_mut(id).unwrap() = 5.0;
+
+        assert_eq!(graph.edge_weight(id), Some(&5.0));
+        assert_eq!(graph.edge_weight_mut(EdgeId(7)), None);
+    }
+
+    #[test]
+    fn graph_conversions() {
+        let mut graph = Graph::<u32, u32>::from_edges(&[(1, 2, 1), (2, 3, 2)]);
+        graph.add_node(4);
+
+        let mut multigraph = MultiGraph::from(graph);
+        assert_eq!(multigraph.nodes().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!(multigraph.edge_count(), 2);
+
+        multigraph.add_edge(2, 3, 10);
+        multigraph.add_edge(3, 2, 20);
+        multigraph.add_edge(2, 3, 30);
+
+        let merged = multigraph.into_graph(|a, b| a + b);
+
+        assert_eq!(merged.nodes().collect::<Vec<_>>(), vec![1, 2, 3, 4]);
+        assert_eq!(
+            merged.all_edges().collect::<Vec<_>>(),
+            vec![(1, 2, &1), (2, 3, &42), (3, 2, &20)]
+        );
+    }
+}
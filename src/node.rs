@@ -1,10 +1,11 @@
 //! Graph Node related constructs.
 
-use crate::edge::CompactDirection;
-use indexmap::map::Keys;
-use std::fmt::Debug;
+use crate::edge::{CompactDirection, Direction, EdgeType};
+use indexmap::map::{Iter as IndexMapIter, IterMut as IndexMapIterMut, Keys};
+use std::fmt::{self, Debug};
 use std::hash::Hash;
 use std::iter::Cloned;
+use std::marker::PhantomData;
 
 /// A trait group for `Graph`'s node identifier.
 pub trait NodeTrait: Copy + Debug + Hash + Ord {}
@@ -12,18 +13,135 @@ pub trait NodeTrait: Copy + Debug + Hash + Ord {}
 /// Implement the `NodeTrait` for all types satisfying bounds.
 impl<N> NodeTrait for N where N: Copy + Debug + Hash + Ord {}
 
+/// Adjacency lists of a node, keeping the outgoing and incoming neighbors apart, together with
+/// the node weight `W`.
+///
+/// Undirected graphs only use the outgoing list. In a directed graph, a self loop is in both
+/// lists.
+#[derive(Clone)]
+pub(crate) struct Adjacency<N, W = ()> {
+    outgoing: Vec<N>,
+    incoming: Vec<N>,
+    weight: W,
+}
+
+impl<N, W> Adjacency<N, W> {
+    /// Create empty adjacency lists of a node weighing `weight`.
+    pub(crate) fn new(weight: W) -> Self {
+        Self {
+            outgoing: Vec::new(),
+            incoming: Vec::new(),
+            weight,
+        }
+    }
+
+    /// Return the node weight.
+    pub(crate) fn weight(&self) -> &W {
+        &self.weight
+    }
+
+    /// Return the node weight mutably.
+    pub(crate) fn weight_mut(&mut self) -> &mut W {
+        &mut self.weight
+    }
+
+    /// Convert the node weight by `f`, keeping the adjacency lists.
+    pub(crate) fn map_weight<W2, F>(self, f: F) -> Adjacency<N, W2>
+    where
+        F: FnOnce(W) -> W2,
+    {
+        Adjacency {
+            outgoing: self.outgoing,
+            incoming: self.incoming,
+            weight: f(self.weight),
+        }
+    }
+
+    /// Return the neighbors in direction `dir`.
+    pub(crate) fn get(&self, dir: Direction) -> &[N] {
+        match dir {
+            Direction::Outgoing => &self.outgoing,
+            Direction::Incoming => &self.incoming,
+        }
+    }
+
+    fn get_mut(&mut self, dir: Direction) -> &mut Vec<N> {
+        match dir {
+            Direction::Outgoing => &mut self.outgoing,
+            Direction::Incoming => &mut self.incoming,
+        }
+    }
+
+    /// Append neighbor `n` in direction `dir` and return its position.
+    pub(crate) fn push(&mut self, dir: Direction, n: N) -> usize {
+        let neighbors = self.get_mut(dir);
+        neighbors.push(n);
+        neighbors.len() - 1
+    }
+
+    /// Remove the neighbor at position `i` in direction `dir` by moving the last one in its
+    /// place, and return the moved neighbor, `None` if `i` was the last position.
+    pub(crate) fn swap_remove(&mut self, dir: Direction, i: usize) -> Option<N>
+    where
+        N: Copy,
+    {
+        let neighbors = self.get_mut(dir);
+        neighbors.swap_remove(i);
+        neighbors.get(i).cloned()
+    }
+
+    /// Remove the first occurrence of neighbor `n` in direction `dir`, keeping the order of
+    /// the others.
+    pub(crate) fn remove(&mut self, dir: Direction, n: N)
+    where
+        N: PartialEq,
+    {
+        let neighbors = self.get_mut(dir);
+        if let Some(i) = neighbors.iter().position(|m| *m == n) {
+            neighbors.remove(i);
+        }
+    }
+
+    /// Return `true` if the node has no neighbor in either direction.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.outgoing.is_empty() && self.incoming.is_empty()
+    }
+}
+
+/// Format the neighbors as a list of `(n, direction)` pairs, the outgoing ones first.
+impl<N: Debug, W> Debug for Adjacency<N, W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let outgoing = self
+            .outgoing
+            .iter()
+            .map(|n| (n, CompactDirection::Outgoing));
+        let incoming = self
+            .incoming
+            .iter()
+            .map(|n| (n, CompactDirection::Incoming));
+
+        f.debug_list().entries(outgoing.chain(incoming)).finish()
+    }
+}
+
+impl<N, W: Default> Default for Adjacency<N, W> {
+    fn default() -> Self {
+        Self::new(W::default())
+    }
+}
+
 /// Iterator over Nodes.
-pub struct Nodes<'a, N: 'a + NodeTrait> {
-    iter: Cloned<Keys<'a, N, Vec<(N, CompactDirection)>>>,
+pub struct Nodes<'a, N: 'a + NodeTrait, W: 'a = ()> {
+    iter: Cloned<Keys<'a, N, Adjacency<N, W>>>,
 }
 
-impl<'a, N: 'a + NodeTrait> Nodes<'a, N> {
-    pub fn new(iter: Cloned<Keys<'a, N, Vec<(N, CompactDirection)>>>) -> Self {
+impl<'a, N: 'a + NodeTrait, W> Nodes<'a, N, W> {
+    pub(crate) fn new(iter: Cloned<Keys<'a, N, Adjacency<N, W>>>) -> Self {
         Self { iter }
     }
 }
 
-impl<'a, N: 'a + NodeTrait> Iterator for Nodes<'a, N> {
+impl<'a, N: 'a + NodeTrait, W> Iterator for Nodes<'a, N, W> {
     type Item = N;
     #[inline]
     fn next(&mut self) -> Option<Self::Item> {
@@ -36,25 +154,121 @@ impl<'a, N: 'a + NodeTrait> Iterator for Nodes<'a, N> {
     }
 }
 
+/// Iterator over the nodes without an edge in a given direction.
+///
+/// - `Directed`, `Outgoing`: Nodes without outgoing edges, the sinks.
+/// - `Directed`, `Incoming`: Nodes without incoming edges, the sources.
+/// - `Undirected`: Nodes without any edge.
+///
+/// A self loop is both an outgoing and an incoming edge.
+pub struct Externals<'a, N: 'a + NodeTrait, Ty, W: 'a = ()> {
+    iter: IndexMapIter<'a, N, Adjacency<N, W>>,
+    dir: Direction,
+    ty: PhantomData<Ty>,
+}
+
+impl<'a, N: 'a + NodeTrait, Ty: EdgeType, W> Externals<'a, N, Ty, W> {
+    pub(crate) fn new(iter: IndexMapIter<'a, N, Adjacency<N, W>>, dir: Direction) -> Self {
+        Self {
+            iter,
+            dir,
+            ty: PhantomData,
+        }
+    }
+}
+
+impl<'a, N: 'a + NodeTrait, Ty: EdgeType, W> Iterator for Externals<'a, N, Ty, W> {
+    type Item = N;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let dir = self.dir;
+
+        self.iter
+            .find(|&(_, neighbors)| {
+                if Ty::is_directed() {
+                    neighbors.get(dir).is_empty()
+                } else {
+                    neighbors.is_empty()
+                }
+            })
+            .map(|(&n, _)| n)
+    }
+}
+
+/// Iterator over the nodes paired with their weight.
+///
+/// Iterator element type is `(&N, &W)`.
+pub struct NodeWeights<'a, N: 'a, W: 'a> {
+    iter: IndexMapIter<'a, N, Adjacency<N, W>>,
+}
+
+impl<'a, N: 'a, W: 'a> NodeWeights<'a, N, W> {
+    pub(crate) fn new(iter: IndexMapIter<'a, N, Adjacency<N, W>>) -> Self {
+        Self { iter }
+    }
+}
+
+impl<'a, N: 'a, W: 'a> Iterator for NodeWeights<'a, N, W> {
+    type Item = (&'a N, &'a W);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next()
+            .map(|(n, adjacency)| (n, adjacency.weight()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Iterator over the nodes paired with their mutable weight.
+///
+/// Iterator element type is `(&N, &mut W)`.
+pub struct NodeWeightsMut<'a, N: 'a, W: 'a> {
+    iter: IndexMapIterMut<'a, N, Adjacency<N, W>>,
+}
+
+impl<'a, N: 'a, W: 'a> NodeWeightsMut<'a, N, W> {
+    pub(crate) fn new(iter: IndexMapIterMut<'a, N, Adjacency<N, W>>) -> Self {
+        Self { iter }
+    }
+}
+
+impl<'a, N: 'a, W: 'a> Iterator for NodeWeightsMut<'a, N, W> {
+    type Item = (&'a N, &'a mut W);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter
+            .next()
+            .map(|(n, adjacency)| (n, adjacency.weight_mut()))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::edge::CompactDirection;
-    use crate::node::Nodes;
+    use crate::edge::Direction::{self, Incoming, Outgoing};
+    use crate::graph::{Directed, Undirected};
+    use crate::node::{Adjacency, Externals, Nodes};
     use indexmap::IndexMap;
 
     #[test]
     fn new() {
-        let nodes: IndexMap<i32, Vec<(i32, CompactDirection)>> = IndexMap::new();
+        let nodes: IndexMap<i32, Adjacency<i32>> = IndexMap::new();
 
         Nodes::new(nodes.keys().cloned());
     }
 
     #[test]
     fn next() {
-        let mut nodes: IndexMap<i32, Vec<(i32, CompactDirection)>> = IndexMap::with_capacity(3);
-        nodes.insert(1, vec![]);
-        nodes.insert(2, vec![]);
-        nodes.insert(3, vec![]);
+        let mut nodes: IndexMap<i32, Adjacency<i32>> = IndexMap::with_capacity(3);
+        nodes.insert(1, Adjacency::default());
+        nodes.insert(2, Adjacency::default());
+        nodes.insert(3, Adjacency::default());
 
         let mut nodes = Nodes::new(nodes.keys().cloned());
 
@@ -67,12 +281,21 @@ mod tests {
         assert_eq!(nodes.next(), None);
     }
 
+    #[test]
+    fn adjacency_fmt() {
+        let mut neighbors: Adjacency<i32> = Adjacency::default();
+        neighbors.push(Incoming, 3);
+        neighbors.push(Outgoing, 2);
+
+        assert_eq!(format!("{:?}", neighbors), "[(2, Outgoing), (3, Incoming)]");
+    }
+
     #[test]
     fn size_hint() {
-        let mut nodes: IndexMap<i32, Vec<(i32, CompactDirection)>> = IndexMap::with_capacity(3);
-        nodes.insert(1, vec![]);
-        nodes.insert(2, vec![]);
-        nodes.insert(3, vec![]);
+        let mut nodes: IndexMap<i32, Adjacency<i32>> = IndexMap::with_capacity(3);
+        nodes.insert(1, Adjacency::default());
+        nodes.insert(2, Adjacency::default());
+        nodes.insert(3, Adjacency::default());
 
         let mut nodes = Nodes::new(nodes.keys().cloned());
 
@@ -93,4 +316,58 @@ mod tests {
 
         assert_eq!(nodes.size_hint(), (0, Some(0)));
     }
+
+    #[test]
+    fn externals() {
+        let mut nodes: IndexMap<i32, Adjacency<i32>> = IndexMap::with_capacity(4);
+        nodes.entry(1).or_default().push(Outgoing, 2);
+        nodes.entry(2).or_default().push(Incoming, 1);
+        nodes.entry(3).or_default().push(Outgoing, 3);
+        nodes.entry(3).or_default().push(Incoming, 3);
+        nodes.insert(4, Adjacency::default());
+
+        let sources: Externals<i32, Directed> = Externals::new(nodes.iter(), Direction::Incoming);
+        assert_eq!(sources.collect::<Vec<_>>(), vec![1, 4]);
+
+        let sinks: Externals<i32, Directed> = Externals::new(nodes.iter(), Direction::Outgoing);
+        assert_eq!(sinks.collect::<Vec<_>>(), vec![2, 4]);
+
+        let isolated: Externals<i32, Undirected> =
+            Externals::new(nodes.iter(), Direction::Outgoing);
+        assert_eq!(isolated.collect::<Vec<_>>(), vec![4]);
+    }
+
+    #[test]
+    fn adjacency() {
+        let mut adjacency: Adjacency<i32> = Adjacency::default();
+        adjacency.push(Outgoing, 2);
+        adjacency.push(Outgoing, 3);
+        adjacency.push(Incoming, 2);
+        assert_eq!(adjacency.push(Outgoing, 2), 2);
+
+        assert_eq!(adjacency.get(Outgoing), &[2, 3, 2]);
+        assert_eq!(adjacency.get(Incoming), &[2]);
+
+        adjacency.remove(Outgoing, 2);
+        assert_eq!(adjacency.get(Outgoing), &[3, 2]);
+
+        assert_eq!(adjacency.swap_remove(Outgoing, 0), Some(2));
+        assert_eq!(adjacency.get(Outgoing), &[2]);
+        assert_eq!(adjacency.swap_remove(Outgoing, 0), None);
+        assert_eq!(adjacency.swap_remove(Incoming, 0), None);
+        assert!(adjacency.is_empty());
+    }
+
+    #[test]
+    fn adjacency_weight() {
+        let mut adjacency = Adjacency::new("a");
+        adjacency.push(Outgoing, 2);
+        *adjacency.weight_mut() = "b";
+
+        // Test the adjacency lists are kept when converting the weight.
+        let adjacency = adjacency.map_weight(str::len);
+
+        assert_eq!(adjacency.weight(), &1);
+        assert_eq!(adjacency.get(Outgoing), &[2]);
+    }
 }
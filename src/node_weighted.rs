@@ -0,0 +1,332 @@
+//! Graph with data attached to its nodes.
+
+use crate::edge::{AllEdgesMut, EdgeType, IntoWeightedEdge};
+use crate::graph::{Directed, Graph};
+use crate::node::{NodeTrait, NodeWeights, NodeWeightsMut};
+use std::collections::hash_map::RandomState;
+use std::fmt;
+use std::ops::Deref;
+
+/// `NodeWeightedGraph<N, W, E, Ty>` is a `Graph` whose nodes `N` carry an associated weight
+/// `W`.
+///
+/// The node `N` stays the identifier, `W` can be of arbitrary type. The weight is stored in the
+/// node entry of the inner `Graph`, so a node and its weight are looked up at once. Graph
+/// queries are available through `Deref` to the inner `Graph`, modifications go through this
+/// type so every node always has its weight.
+///
+/// # Examples
+///
+/// ```
+/// use safe_graph::NodeWeightedGraph;
+///
+/// let mut graph: NodeWeightedGraph<u32, String, f32> = NodeWeightedGraph::new();
+/// graph.add_node_with(1, "start".to_string());
+/// graph.add_edge_or_default(1, 2, 0.5);
+///
+/// assert_eq!(graph.node_weight(1).map(String::as_str), Some("start"));
+/// // Nodes inserted by edges get the default weight.
+/// assert_eq!(graph.node_weight(2).map(String::as_str), Some(""));
+/// // Graph queries are forwarded.
+/// assert_eq!(graph.neighbors(1).collect::<Vec<_>>(), vec![2]);
+/// ```
+#[derive(Clone)]
+pub struct NodeWeightedGraph<N, W, E, Ty = Directed> {
+    graph: Graph<N, E, Ty, RandomState, W>,
+}
+
+impl<N, W, E, Ty> fmt::Debug for NodeWeightedGraph<N, W, E, Ty>
+where
+    N: NodeTrait,
+    W: fmt::Debug,
+    E: fmt::Debug,
+    Ty: EdgeType,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NodeWeightedGraph")
+            .field("graph", &self.graph)
+            .field("weights", &self.node_weights().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+impl<N, W, E, Ty> NodeWeightedGraph<N, W, E, Ty>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+{
+    /// Create a new `NodeWeightedGraph` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new `NodeWeightedGraph` with estimated capacity.
+    pub fn with_capacity(nodes: usize, edges: usize) -> Self {
+        Self {
+            graph: Graph::with_capacity_default(nodes, edges),
+        }
+    }
+
+    /// Remove all nodes and edges.
+    pub fn clear(&mut self) {
+        self.graph.clear();
+    }
+
+    /// Add node `n` with associated data `weight` to the graph.
+    ///
+    /// Return `None` if the node did not previously exist, otherwise, the associated data is
+    /// updated and the old value is returned as `Some(old_weight)`.
+    pub fn add_node_with(&mut self, n: N, weight: W) -> Option<W> {
+        self.graph.add_node_with(n, weight)
+    }
+
+    /// Add node `n` to the graph with the default weight, an existing node is left unchanged.
+    pub fn add_node(&mut self, n: N) -> N
+    where
+        W: Default,
+    {
+        self.graph.add_node(n)
+    }
+
+    /// Add an edge connecting the existing nodes `a` and `b` to the graph, with associated data
+    /// `weight`. For a directed graph, the edge is directed from `a` to `b`.
+    ///
+    /// Return the old weight of the edge like `Graph::add_edge`, or `Err(weight)` handing the
+    /// weight back if `a` or `b` is not part of the graph. Use `add_edge_or_default` to insert
+    /// missing nodes.
+    pub fn add_edge(&mut self, a: N, b: N, weight: E) -> Result<Option<E>, E> {
+        if !self.graph.contains_node(a) || !self.graph.contains_node(b) {
+            return Err(weight);
+        }
+
+        Ok(self.graph.add_edge_with(a, b, weight, || {
+            unreachable!("Edge node is part of the graph.")
+        }))
+    }
+
+    /// Add an edge connecting `a` and `b` to the graph, with associated data `weight`. For a
+    /// directed graph, the edge is directed from `a` to `b`.
+    ///
+    /// Inserts nodes `a` and/or `b` with the default weight if they aren't already part of the
+    /// graph. Return the old weight of the edge like `Graph::add_edge`.
+    pub fn add_edge_or_default(&mut self, a: N, b: N, weight: E) -> Option<E>
+    where
+        W: Default,
+    {
+        self.graph.add_edge(a, b, weight)
+    }
+
+    /// Return a reference to the weight of node `n`, or `None` if the node does not exist in
+    /// the graph.
+    pub fn node_weight(&self, n: N) -> Option<&W> {
+        self.graph.node_weight(n)
+    }
+
+    /// Return a mutable reference to the weight of node `n`, or `None` if the node does not
+    /// exist in the graph.
+    pub fn node_weight_mut(&mut self, n: N) -> Option<&mut W> {
+        self.graph.node_weight_mut(n)
+    }
+
+    /// Return an iterator over the nodes of the graph paired with their weight, in node
+    /// insertion order.
+    ///
+    /// Iterator element type is `(&N, &W)`.
+    pub fn node_weights(&self) -> NodeWeights<'_, N, W> {
+        self.graph.node_weights()
+    }
+
+    /// Return an iterator over the nodes of the graph paired with their mutable weight, in
+    /// node insertion order.
+    ///
+    /// Iterator element type is `(&N, &mut W)`.
+    pub fn node_weights_mut(&mut self) -> NodeWeightsMut<'_, N, W> {
+        self.graph.node_weights_mut()
+    }
+
+    /// Return a mutable reference to the edge weight connecting `a` with `b`, or
+    /// `None` if the edge does not exist in the graph.
+    pub fn edge_weight_mut(&mut self, a: N, b: N) -> Option<&mut E> {
+        self.graph.edge_weight_mut(a, b)
+    }
+
+    /// Return an iterator over all edges of the graph with their mutable weight, in edge
+    /// insertion order.
+    ///
+    /// Iterator element type is `(N, N, &mut E)`
+    pub fn all_edges_mut(&mut self) -> AllEdgesMut<'_, N, E, Ty> {
+        self.graph.all_edges_mut()
+    }
+
+    /// Return the inner `Graph`.
+    pub fn graph(&self) -> &Graph<N, E, Ty, RandomState, W> {
+        &self.graph
+    }
+
+    /// Convert into a `Graph` without node weights and the node weights in node insertion
+    /// order.
+    pub fn into_parts(self) -> (Graph<N, E, Ty>, Vec<(N, W)>) {
+        let mut weights = Vec::with_capacity(self.graph.node_count());
+        let graph = self
+            .graph
+            .map_node_weights(|n, weight| weights.push((n, weight)));
+
+        (graph, weights)
+    }
+}
+
+impl<N, W, E, Ty> Deref for NodeWeightedGraph<N, W, E, Ty> {
+    type Target = Graph<N, E, Ty, RandomState, W>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.graph
+    }
+}
+
+/// Create a new empty `NodeWeightedGraph`.
+impl<N, W, E, Ty> Default for NodeWeightedGraph<N, W, E, Ty>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+{
+    fn default() -> Self {
+        NodeWeightedGraph::with_capacity(0, 0)
+    }
+}
+
+/// Convert a `Graph` into a `NodeWeightedGraph`, all nodes having the default weight.
+impl<N, W, E, Ty> From<Graph<N, E, Ty>> for NodeWeightedGraph<N, W, E, Ty>
+where
+    N: NodeTrait,
+    W: Default,
+    Ty: EdgeType,
+{
+    fn from(graph: Graph<N, E, Ty>) -> Self {
+        Self {
+            graph: graph.map_node_weights(|_, ()| W::default()),
+        }
+    }
+}
+
+/// Extend the graph from an iterable of edges.
+///
+/// Nodes are inserted automatically with the default weight to match the edges.
+impl<N, W, E, Ty, Item> Extend<Item> for NodeWeightedGraph<N, W, E, Ty>
+where
+    Item: IntoWeightedEdge<E, NodeId = N>,
+    N: NodeTrait,
+    W: Default,
+    Ty: EdgeType,
+{
+    fn extend<I>(&mut self, iterable: I)
+    where
+        I: IntoIterator<Item = Item>,
+    {
+        for elt in iterable {
+            let (source, target, weight) = elt.into_weighted_edge();
+            self.add_edge_or_default(source, target, weight);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{Graph, Undirected};
+    use crate::node_weighted::NodeWeightedGraph;
+
+    #[test]
+    fn add_node_with() {
+        let mut graph: NodeWeightedGraph<u32, &str, f32> = NodeWeightedGraph::new();
+
+        assert_eq!(graph.add_node_with(1, "a"), None);
+        assert_eq!(graph.add_node_with(2, "b"), None);
+        assert_eq!(graph.add_node_with(1, "c"), Some("a"));
+
+        // Test the node order is kept on update.
+        assert_eq!(graph.nodes().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(graph.node_weight(1), Some(&"c"));
+        assert_eq!(graph.node_weight(3), None);
+    }
+
+    #[test]
+    fn add_edge() {
+        let mut graph: NodeWeightedGraph<u32, u32, f32, Undirected> = NodeWeightedGraph::new();
+        graph.add_node_with(2, 7);
+        graph.add_edge_or_default(1, 2, 0.5);
+        graph.extend(vec![(3, 1, 1.5)]);
+
+        assert_eq!(
+            graph.node_weights().collect::<Vec<_>>(),
+            vec![(&2, &7), (&1, &0), (&3, &0)]
+        );
+        assert_eq!(graph.edge_count(), 2);
+        assert_eq!(graph.edge_weight(1, 3), Some(&1.5));
+
+        // Test an existing node keeps its weight.
+        graph.add_node(2);
+        assert_eq!(graph.node_weight(2), Some(&7));
+    }
+
+    #[test]
+    fn add_edge_existing_nodes() {
+        // Test node weights without a default value.
+        struct Label(&'static str);
+
+        let mut graph: NodeWeightedGraph<u32, Label, f32> = NodeWeightedGraph::new();
+        graph.add_node_with(1, Label("a"));
+        graph.add_node_with(2, Label("b"));
+
+        assert_eq!(graph.add_edge(1, 2, 0.5), Ok(None));
+        assert_eq!(graph.add_edge(1, 2, 1.5), Ok(Some(0.5)));
+        assert_eq!(graph.node_weight(2).map(|label| label.0), Some("b"));
+    }
+
+    #[test]
+    fn add_edge_missing_node() {
+        let mut graph: NodeWeightedGraph<u32, u32, f32> = NodeWeightedGraph::new();
+        graph.add_node_with(1, 0);
+
+        // Test the weight is handed back and the graph is left unchanged.
+        assert_eq!(graph.add_edge(1, 2, 0.5), Err(0.5));
+        assert_eq!(graph.add_edge(2, 1, 1.5), Err(1.5));
+        assert_eq!(graph.node_count(), 1);
+        assert_eq!(graph.edge_count(), 0);
+    }
+
+    #[test]
+    fn weights_mut() {
+        let mut graph: NodeWeightedGraph<u32, u32, f32> = NodeWeightedGraph::new();
+        graph.add_edge_or_default(1, 2, 0.5);
+
+        *graph.node_weight_mut(1).unwrap() = 10;
+        for (&n, weight) in graph.node_weights_mut() {
+            *weight += n;
+        }
+        *graph.edge_weight_mut(1, 2).unwrap() = 2.5;
+
+        assert_eq!(graph.node_weight(1), Some(&11));
+        assert_eq!(graph.node_weight(2), Some(&2));
+        assert_eq!(graph.edge_weight(1, 2), Some(&2.5));
+    }
+
+    #[test]
+    fn graph_conversions() {
+        let mut graph = Graph::<u32, f32>::from_edges(&[(1, 2, 0.5)]);
+        graph.add_node(3);
+
+        let mut weighted: NodeWeightedGraph<u32, String, f32> = NodeWeightedGraph::from(graph);
+        weighted.add_node_with(3, "three".to_string());
+
+        let (graph, weights) = weighted.into_parts();
+
+        assert_eq!(graph.node_count(), 3);
+        assert_eq!(
+            weights,
+            vec![
+                (1, String::new()),
+                (2, String::new()),
+                (3, "three".to_string())
+            ]
+        );
+    }
+}
@@ -0,0 +1,534 @@
+//! Graph keeping node and edge indices stable across removals.
+
+use crate::edge::{
+    Direction::{Incoming, Outgoing},
+    EdgeType,
+};
+use crate::graph::{Directed, Graph};
+use crate::node::{Adjacency, NodeTrait};
+use crate::traverse::Neighbors;
+use std::collections::HashMap;
+use std::fmt;
+use std::hash::BuildHasher;
+use std::marker::PhantomData;
+
+/// Index of a node of a `StableGraph`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct NodeIndex(usize);
+
+impl NodeIndex {
+    /// Create a new `NodeIndex` from `usize`.
+    pub fn new(index: usize) -> Self {
+        NodeIndex(index)
+    }
+
+    /// Return the index as `usize`.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// Index of an edge of a `StableGraph`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EdgeIndex(usize);
+
+impl EdgeIndex {
+    /// Create a new `EdgeIndex` from `usize`.
+    pub fn new(index: usize) -> Self {
+        EdgeIndex(index)
+    }
+
+    /// Return the index as `usize`.
+    pub fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// Mapping of the old indices to the new ones, returned by `StableGraph::compact`.
+///
+/// Indices of removed slots map to `None`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Remap {
+    pub nodes: Vec<Option<NodeIndex>>,
+    pub edges: Vec<Option<EdgeIndex>>,
+}
+
+type NodeSlot<N> = Option<(N, Adjacency<N>)>;
+type EdgeSlot<N, E> = Option<(N, N, E)>;
+
+/// `StableGraph<N, E, Ty>` is a graph whose node and edge indices stay valid when other nodes
+/// and edges are removed.
+///
+/// Like `Graph`, nodes `N` are their own identifiers and parallel edges are not allowed.
+/// Nodes and edges are stored in slots, a removal leaves an empty slot which is put on a free
+/// list and reused by the next insertion. `compact` removes the empty slots, returning how the
+/// indices moved.
+///
+/// # Examples
+///
+/// ```
+/// use safe_graph::StableGraph;
+///
+/// let mut graph: StableGraph<&str, u32> = StableGraph::new();
+/// let a = graph.add_node("a");
+/// graph.add_node("b");
+/// let c = graph.add_node("c");
+/// let bc = graph.add_edge("b", "c", 1);
+///
+/// graph.remove_node("b");
+///
+/// // Indices of the remaining nodes are unchanged.
+/// assert_eq!(graph.node_index("c"), Some(c));
+/// assert_eq!(graph.edge_endpoints(bc), None);
+///
+/// let remap = graph.compact();
+/// assert_eq!(remap.nodes[a.index()], Some(a));
+/// assert_eq!(graph.node_index("c").map(|i| i.index()), Some(1));
+/// ```
+#[derive(Clone)]
+pub struct StableGraph<N, E, Ty = Directed> {
+    nodes: Vec<NodeSlot<N>>,
+    edges: Vec<EdgeSlot<N, E>>,
+    node_indices: HashMap<N, NodeIndex>,
+    edge_indices: HashMap<(N, N), EdgeIndex>,
+    free_nodes: Vec<NodeIndex>,
+    free_edges: Vec<EdgeIndex>,
+    ty: PhantomData<Ty>,
+}
+
+impl<N, E, Ty> fmt::Debug for StableGraph<N, E, Ty>
+where
+    N: NodeTrait,
+    E: fmt::Debug,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let nodes: Vec<_> = self
+            .nodes
+            .iter()
+            .map(|slot| slot.as_ref().map(|s| s.0))
+            .collect();
+
+        f.debug_struct("StableGraph")
+            .field("nodes", &nodes)
+            .field("edges", &self.edges)
+            .finish()
+    }
+}
+
+impl<N, E, Ty> StableGraph<N, E, Ty>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+{
+    /// Create a new `StableGraph` instance.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new `StableGraph` with estimated capacity.
+    pub fn with_capacity(nodes: usize, edges: usize) -> Self {
+        Self {
+            nodes: Vec::with_capacity(nodes),
+            edges: Vec::with_capacity(edges),
+            node_indices: HashMap::with_capacity(nodes),
+            edge_indices: HashMap::with_capacity(edges),
+            free_nodes: Vec::new(),
+            free_edges: Vec::new(),
+            ty: PhantomData,
+        }
+    }
+
+    /// Whether the graph has directed edges.
+    pub fn is_directed(&self) -> bool {
+        Ty::is_directed()
+    }
+
+    /// Return the number of nodes in the graph.
+    pub fn node_count(&self) -> usize {
+        self.node_indices.len()
+    }
+
+    /// Return the number of edges in the graph.
+    pub fn edge_count(&self) -> usize {
+        self.edge_indices.len()
+    }
+
+    /// Return the number of node slots, an upper bound of the node indices.
+    pub fn node_bound(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Return the number of edge slots, an upper bound of the edge indices.
+    pub fn edge_bound(&self) -> usize {
+        self.edges.len()
+    }
+
+    /// Add node `n` to the graph and return its index, the existing index if the node is
+    /// already part of the graph.
+    pub fn add_node(&mut self, n: N) -> NodeIndex {
+        if let Some(&i) = self.node_indices.get(&n) {
+            return i;
+        }
+
+        let slot = Some((n, Adjacency::default()));
+        let i = match self.free_nodes.pop() {
+            Some(i) => {
+                self.nodes[i.0] = slot;
+                i
+            }
+            None => {
+                self.nodes.push(slot);
+                NodeIndex(self.nodes.len() - 1)
+            }
+        };
+        self.node_indices.insert(n, i);
+        i
+    }
+
+    /// Remove node `n` and all its edges from the graph and return its index, or `None` if
+    /// the node does not exist.
+    ///
+    /// The outgoing edges are removed before the incoming ones, the index of the last removed
+    /// edge being reused first.
+    pub fn remove_node(&mut self, n: N) -> Option<NodeIndex> {
+        let i = self.node_index(n)?;
+
+        let neighbors = self.adjacency(i).clone();
+        for &m in neighbors.get(Outgoing) {
+            self.remove_edge(n, m);
+        }
+        for &m in neighbors.get(Incoming) {
+            self.remove_edge(m, n);
+        }
+
+        self.node_indices.remove(&n);
+        self.nodes[i.0] = None;
+        self.free_nodes.push(i);
+
+        Some(i)
+    }
+
+    /// Return `true` if the node is contained in the graph.
+    pub fn contains_node(&self, n: N) -> bool {
+        self.node_indices.contains_key(&n)
+    }
+
+    /// Return the index of node `n`, or `None` if the node does not exist in the graph.
+    pub fn node_index(&self, n: N) -> Option<NodeIndex> {
+        self.node_indices.get(&n).cloned()
+    }
+
+    /// Return the node at index `i`, or `None` if the slot is empty or out of bounds.
+    pub fn node_at(&self, i: NodeIndex) -> Option<N> {
+        self.nodes.get(i.0)?.as_ref().map(|&(n, _)| n)
+    }
+
+    /// Add an edge connecting `a` and `b` to the graph, with associated data `weight`, and
+    /// return its index. For a directed graph, the edge is directed from `a` to `b`.
+    ///
+    /// Inserts nodes `a` and/or `b` if they aren't already part of the graph. If the edge
+    /// already exists, its weight is updated and its index is kept.
+    pub fn add_edge(&mut self, a: N, b: N, weight: E) -> EdgeIndex {
+        let key = Graph::<N, E, Ty>::edge_key(a, b);
+        if let Some(&i) = self.edge_indices.get(&key) {
+            if let Some((_, _, ref mut old)) = self.edges[i.0] {
+                *old = weight;
+            }
+            return i;
+        }
+
+        let ai = self.add_node(a);
+        let bi = self.add_node(b);
+        self.adjacency_mut(ai).push(Outgoing, b);
+        // Undirected self loops are listed once.
+        if Ty::is_directed() {
+            self.adjacency_mut(bi).push(Incoming, a);
+        } else if a != b {
+            self.adjacency_mut(bi).push(Outgoing, a);
+        }
+
+        let slot = Some((a, b, weight));
+        let i = match self.free_edges.pop() {
+            Some(i) => {
+                self.edges[i.0] = slot;
+                i
+            }
+            None => {
+                self.edges.push(slot);
+                EdgeIndex(self.edges.len() - 1)
+            }
+        };
+        self.edge_indices.insert(key, i);
+        i
+    }
+
+    /// Remove the edge connecting `a` with `b` and return its weight, or `None` if the edge
+    /// does not exist. The nodes are kept.
+    pub fn remove_edge(&mut self, a: N, b: N) -> Option<E> {
+        let i = self
+            .edge_indices
+            .remove(&Graph::<N, E, Ty>::edge_key(a, b))?;
+        let (a, b, weight) = self.edges[i.0].take().expect("Edge slot is empty.");
+        self.free_edges.push(i);
+
+        let ai = self.node_indices[&a];
+        self.adjacency_mut(ai).remove(Outgoing, b);
+        let bi = self.node_indices[&b];
+        if Ty::is_directed() {
+            self.adjacency_mut(bi).remove(Incoming, a);
+        } else if a != b {
+            self.adjacency_mut(bi).remove(Outgoing, a);
+        }
+
+        Some(weight)
+    }
+
+    /// Return `true` if the edge connecting `a` with `b` is contained in the graph.
+    pub fn contains_edge(&self, a: N, b: N) -> bool {
+        self.edge_index(a, b).is_some()
+    }
+
+    /// Return the index of the edge connecting `a` with `b`, or `None` if the edge does not
+    /// exist in the graph.
+    pub fn edge_index(&self, a: N, b: N) -> Option<EdgeIndex> {
+        self.edge_indices
+            .get(&Graph::<N, E, Ty>::edge_key(a, b))
+            .cloned()
+    }
+
+    /// Return the nodes `(a, b)` of edge `i` as they were added, or `None` if the slot is
+    /// empty or out of bounds.
+    pub fn edge_endpoints(&self, i: EdgeIndex) -> Option<(N, N)> {
+        self.edges.get(i.0)?.as_ref().map(|&(a, b, _)| (a, b))
+    }
+
+    /// Return a reference to the edge weight connecting `a` with `b`, or
+    /// `None` if the edge does not exist in the graph.
+    pub fn edge_weight(&self, a: N, b: N) -> Option<&E> {
+        let i = self.edge_index(a, b)?;
+        self.edges[i.0].as_ref().map(|(_, _, weight)| weight)
+    }
+
+    /// Return a mutable reference to the edge weight connecting `a` with `b`, or
+    /// `None` if the edge does not exist in the graph.
+    pub fn edge_weight_mut(&mut self, a: N, b: N) -> Option<&mut E> {
+        let i = self.edge_index(a, b)?;
+        self.edges[i.0].as_mut().map(|(_, _, weight)| weight)
+    }
+
+    /// Return an iterator over the nodes of the graph with their index, in index order.
+    ///
+    /// Iterator element type is `(NodeIndex, N)`.
+    pub fn nodes(&self) -> impl Iterator<Item = (NodeIndex, N)> + '_ {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter_map(|(i, slot)| slot.as_ref().map(|&(n, _)| (NodeIndex(i), n)))
+    }
+
+    /// Return an iterator of all nodes with an edge starting from `a`.
+    ///
+    /// - `Directed`: Outgoing edges from `a`.
+    /// - `Undirected`: All edges from or to `a`.
+    ///
+    /// Produces an empty iterator if the node doesn't exist.<br>
+    /// Iterator element type is `N`.
+    pub fn neighbors(&self, a: N) -> Neighbors<'_, N, Ty> {
+        let iter = match self.node_indices.get(&a) {
+            Some(&i) => self.adjacency(i).get(Outgoing).iter(),
+            None => [].iter(),
+        };
+
+        Neighbors::new(iter, self.ty)
+    }
+
+    /// Return an iterator over all edges of the graph with their index, in index order.
+    ///
+    /// Iterator element type is `(EdgeIndex, N, N, &E)`.
+    pub fn all_edges(&self) -> impl Iterator<Item = (EdgeIndex, N, N, &E)> {
+        self.edges.iter().enumerate().filter_map(|(i, slot)| {
+            slot.as_ref()
+                .map(|(a, b, weight)| (EdgeIndex(i), *a, *b, weight))
+        })
+    }
+
+    /// Remove the empty node and edge slots, keeping the order of the others, and return the
+    /// mapping of the old indices to the new ones.
+    ///
+    /// The free lists are emptied, all indices given out before are invalidated.
+    pub fn compact(&mut self) -> Remap {
+        let mut remap = Remap {
+            nodes: Vec::with_capacity(self.nodes.len()),
+            edges: Vec::with_capacity(self.edges.len()),
+        };
+
+        let mut nodes = Vec::with_capacity(self.node_count());
+        for slot in self.nodes.drain(..) {
+            match slot {
+                Some((n, neighbors)) => {
+                    let i = NodeIndex(nodes.len());
+                    remap.nodes.push(Some(i));
+                    self.node_indices.insert(n, i);
+                    nodes.push(Some((n, neighbors)));
+                }
+                None => remap.nodes.push(None),
+            }
+        }
+
+        let mut edges = Vec::with_capacity(self.edge_count());
+        for slot in self.edges.drain(..) {
+            match slot {
+                Some((a, b, weight)) => {
+                    let i = EdgeIndex(edges.len());
+                    remap.edges.push(Some(i));
+                    self.edge_indices
+                        .insert(Graph::<N, E, Ty>::edge_key(a, b), i);
+                    edges.push(Some((a, b, weight)));
+                }
+                None => remap.edges.push(None),
+            }
+        }
+
+        self.nodes = nodes;
+        self.edges = edges;
+        self.free_nodes.clear();
+        self.free_edges.clear();
+
+        remap
+    }
+
+    fn adjacency(&self, i: NodeIndex) -> &Adjacency<N> {
+        &self.nodes[i.0].as_ref().expect("Node slot is empty.").1
+    }
+
+    fn adjacency_mut(&mut self, i: NodeIndex) -> &mut Adjacency<N> {
+        &mut self.nodes[i.0].as_mut().expect("Node slot is empty.").1
+    }
+}
+
+/// Create a new empty `StableGraph`.
+impl<N, E, Ty> Default for StableGraph<N, E, Ty>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+{
+    fn default() -> Self {
+        StableGraph::with_capacity(0, 0)
+    }
+}
+
+/// Convert a `Graph` into a `StableGraph`, indices following the node and edge order.
+impl<N, E, Ty, S> From<Graph<N, E, Ty, S>> for StableGraph<N, E, Ty>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    fn from(graph: Graph<N, E, Ty, S>) -> Self {
+        let (nodes, edges) = graph.into_nodes_edges();
+
+        let mut stable = Self::with_capacity(nodes.len(), edges.len());
+        for n in nodes {
+            stable.add_node(n);
+        }
+        for (a, b, weight) in edges {
+            stable.add_edge(a, b, weight);
+        }
+        stable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::graph::{Graph, Undirected};
+    use crate::stable::{EdgeIndex, NodeIndex, StableGraph};
+
+    #[test]
+    fn add_remove() {
+        let mut graph: StableGraph<u32, f32> = StableGraph::new();
+        let e12 = graph.add_edge(1, 2, 1.0);
+        let e23 = graph.add_edge(2, 3, 2.0);
+        let e31 = graph.add_edge(3, 1, 3.0);
+        let e22 = graph.add_edge(2, 2, 4.0);
+
+        assert_eq!(graph.add_edge(1, 2, 5.0), e12);
+        assert_eq!(graph.edge_weight(1, 2), Some(&5.0));
+
+        assert_eq!(graph.remove_node(2), Some(NodeIndex::new(1)));
+        assert_eq!(graph.remove_node(2), None);
+
+        // Test removed edges leave empty slots, other indices are unchanged.
+        assert_eq!(graph.node_count(), 2);
+        assert_eq!(graph.node_bound(), 3);
+        assert_eq!(graph.edge_count(), 1);
+        assert_eq!(graph.edge_bound(), 4);
+        assert_eq!(graph.node_index(3), Some(NodeIndex::new(2)));
+        assert_eq!(graph.node_at(NodeIndex::new(1)), None);
+        assert_eq!(graph.edge_index(3, 1), Some(e31));
+        assert_eq!(graph.edge_endpoints(e23), None);
+        assert_eq!(graph.edge_endpoints(e22), None);
+        assert_eq!(graph.neighbors(1).next(), None);
+        assert_eq!(graph.neighbors(3).collect::<Vec<_>>(), vec![1]);
+
+        // Test the free slots are reused.
+        assert_eq!(graph.add_node(4), NodeIndex::new(1));
+        // The slot of the last removed edge comes first.
+        assert_eq!(graph.add_edge(4, 1, 6.0), e12);
+        assert_eq!(graph.edge_count(), 2);
+    }
+
+    #[test]
+    fn remove_edge() {
+        let mut graph: StableGraph<u32, f32, Undirected> = StableGraph::new();
+        graph.add_edge(1, 2, 1.0);
+        graph.add_edge(2, 3, 2.0);
+
+        assert_eq!(graph.remove_edge(2, 1), Some(1.0));
+        assert_eq!(graph.remove_edge(2, 1), None);
+        assert_eq!(graph.neighbors(2).collect::<Vec<_>>(), vec![3]);
+        assert_eq!(graph.neighbors(1).next(), None);
+        assert_eq!(graph.node_count(), 3);
+
+        *graph.edge_weight_mut(3, 2).unwrap() = 7.0;
+        assert_eq!(
+            graph.all_edges().collect::<Vec<_>>(),
+            vec![(EdgeIndex::new(1), 2, 3, &7.0)]
+        );
+    }
+
+    #[test]
+    fn compact() {
+        let mut graph = Graph::<u32, u32>::from_edges(&[(1, 2, 1), (2, 3, 2), (3, 4, 3)]);
+        graph.add_node(5);
+        let mut graph = StableGraph::from(graph);
+
+        graph.remove_node(2);
+        let remap = graph.compact();
+
+        assert_eq!(
+            remap.nodes,
+            vec![
+                Some(NodeIndex::new(0)),
+                None,
+                Some(NodeIndex::new(1)),
+                Some(NodeIndex::new(2)),
+                Some(NodeIndex::new(3))
+            ]
+        );
+        assert_eq!(remap.edges, vec![None, None, Some(EdgeIndex::new(0))]);
+        assert_eq!(
+            graph.nodes().collect::<Vec<_>>(),
+            vec![
+                (NodeIndex::new(0), 1),
+                (NodeIndex::new(1), 3),
+                (NodeIndex::new(2), 4),
+                (NodeIndex::new(3), 5)
+            ]
+        );
+        assert_eq!(graph.edge_index(3, 4), Some(EdgeIndex::new(0)));
+        assert_eq!(graph.node_bound(), 4);
+
+        // Test new slots are appended after compaction.
+        assert_eq!(graph.add_node(6), NodeIndex::new(4));
+    }
+}
@@ -0,0 +1,694 @@
+//! Graph traits for generic graph traversal and algorithms.
+//!
+//! The traits describe the capabilities of a graph, so code written against them works with
+//! any graph type implementing them, e.g. `&Graph`, graph adaptors and views or custom wrapper
+//! types. They are modelled after the `visit` module of the `petgraph` crate.
+//!
+//! Traits consuming `self` (`Into*`) are implemented for references like `&Graph`, the others
+//! for the graph itself and forwarded for references.
+
+mod filter;
+mod reversed;
+mod undirected;
+
+pub use self::filter::{
+    EdgeFiltered, EdgeFilteredEdges, EdgeFilteredNeighbors, EdgeFilteredNeighborsDirected,
+    FilterEdge, FilterNode, NodeFiltered, NodeFilteredEdges, NodeFilteredNeighbors,
+    NodeFilteredNodes,
+};
+pub use self::reversed::{Reversed, ReversedEdgeReferences, ReversedEdges};
+pub use self::undirected::{
+    AsUndirected, UndirectedEdgeReferences, UndirectedEdges, UndirectedNeighbors,
+};
+
+use crate::csr::{Csr, CsrAllEdges, CsrEdges, CsrNeighbors};
+use crate::edge::{AllEdges, Direction, EdgeType, Edges, EdgesDirected};
+use crate::graph::Graph;
+use crate::node::{NodeTrait, Nodes};
+use crate::traverse::{Neighbors, NeighborsDirected};
+use std::collections::{HashSet, VecDeque};
+use std::hash::{BuildHasher, Hash};
+use std::iter::Cloned;
+use std::slice;
+
+/// Base graph trait defining the node and edge identifier types.
+pub trait GraphBase {
+    /// Node identifier.
+    type NodeId: Copy + Eq + Hash;
+    /// Edge identifier.
+    type EdgeId: Copy + Eq + Hash;
+}
+
+/// A copyable reference to a graph.
+pub trait GraphRef: Copy + GraphBase {}
+
+/// Node and edge weight types of a graph.
+pub trait Data: GraphBase {
+    type NodeWeight;
+    type EdgeWeight;
+}
+
+/// Access to the neighbors of a node.
+pub trait IntoNeighbors: GraphRef {
+    type Neighbors: Iterator<Item = Self::NodeId>;
+
+    /// Return an iterator of the neighbors of node `a`.
+    ///
+    /// - `Directed`: Targets of outgoing edges from `a`.
+    /// - `Undirected`: All nodes connected to `a`.
+    fn neighbors(self, a: Self::NodeId) -> Self::Neighbors;
+}
+
+/// Access to the neighbors of a node in a given direction.
+pub trait IntoNeighborsDirected: IntoNeighbors {
+    type NeighborsDirected: Iterator<Item = Self::NodeId>;
+
+    /// Return an iterator of the neighbors of node `a` in the direction `dir`.
+    fn neighbors_directed(self, a: Self::NodeId, dir: Direction) -> Self::NeighborsDirected;
+}
+
+/// Access to all node identifiers of a graph.
+pub trait IntoNodeIdentifiers: GraphRef {
+    type NodeIdentifiers: Iterator<Item = Self::NodeId>;
+
+    /// Return an iterator of all nodes of the graph.
+    fn node_identifiers(self) -> Self::NodeIdentifiers;
+}
+
+/// A reference to an edge of a graph.
+pub trait EdgeRef: Copy {
+    type NodeId;
+    type Weight;
+
+    /// Return the source node of the edge.
+    fn source(&self) -> Self::NodeId;
+
+    /// Return the target node of the edge.
+    fn target(&self) -> Self::NodeId;
+
+    /// Return the weight of the edge.
+    fn weight(&self) -> &Self::Weight;
+}
+
+impl<N, E> EdgeRef for (N, N, &E)
+where
+    N: Copy,
+{
+    type NodeId = N;
+    type Weight = E;
+
+    fn source(&self) -> N {
+        self.0
+    }
+
+    fn target(&self) -> N {
+        self.1
+    }
+
+    fn weight(&self) -> &E {
+        self.2
+    }
+}
+
+/// Access to all edges of a graph.
+pub trait IntoEdgeReferences: Data + GraphRef {
+    type EdgeRef: EdgeRef<NodeId = Self::NodeId, Weight = Self::EdgeWeight>;
+    type EdgeReferences: Iterator<Item = Self::EdgeRef>;
+
+    /// Return an iterator of all edges of the graph.
+    fn edge_references(self) -> Self::EdgeReferences;
+}
+
+/// Access to the edges of a node.
+pub trait IntoEdges: IntoEdgeReferences + IntoNeighbors {
+    type Edges: Iterator<Item = Self::EdgeRef>;
+
+    /// Return an iterator of the edges starting from node `a`, `a` being the source of the
+    /// edge references.
+    ///
+    /// - `Directed`: Outgoing edges from `a`.
+    /// - `Undirected`: All edges connected to `a`.
+    fn edges(self, a: Self::NodeId) -> Self::Edges;
+}
+
+/// Access to the edges of a node in a given direction.
+pub trait IntoEdgesDirected: IntoEdges + IntoNeighborsDirected {
+    type EdgesDirected: Iterator<Item = Self::EdgeRef>;
+
+    /// Return an iterator of the edges of node `a` in the direction `dir`, the edge references
+    /// keeping their orientation.
+    ///
+    /// - `Outgoing`: `a` is the source of the edge references.
+    /// - `Incoming`: `a` is the target of the edge references.
+    fn edges_directed(self, a: Self::NodeId, dir: Direction) -> Self::EdgesDirected;
+}
+
+/// Number of nodes of a graph.
+pub trait NodeCount: GraphBase {
+    fn node_count(&self) -> usize;
+}
+
+/// A set of visited nodes.
+pub trait VisitMap<N> {
+    /// Mark node `n` as visited.
+    ///
+    /// Return `true` if the node was not visited before.
+    fn visit(&mut self, n: N) -> bool;
+
+    /// Return `true` if node `n` has been visited.
+    fn is_visited(&self, n: &N) -> bool;
+}
+
+impl<N> VisitMap<N> for HashSet<N>
+where
+    N: Eq + Hash,
+{
+    fn visit(&mut self, n: N) -> bool {
+        self.insert(n)
+    }
+
+    fn is_visited(&self, n: &N) -> bool {
+        self.contains(n)
+    }
+}
+
+/// A graph able to create a map tracking visited nodes.
+pub trait Visitable: GraphBase {
+    type Map: VisitMap<Self::NodeId>;
+
+    /// Create a new empty visit map.
+    fn visit_map(&self) -> Self::Map;
+
+    /// Reset the visit map to be reused for another traversal.
+    fn reset_map(&self, map: &mut Self::Map);
+}
+
+impl<G> GraphBase for &G
+where
+    G: GraphBase,
+{
+    type NodeId = G::NodeId;
+    type EdgeId = G::EdgeId;
+}
+
+impl<G> Data for &G
+where
+    G: Data,
+{
+    type NodeWeight = G::NodeWeight;
+    type EdgeWeight = G::EdgeWeight;
+}
+
+impl<G> NodeCount for &G
+where
+    G: NodeCount,
+{
+    fn node_count(&self) -> usize {
+        (**self).node_count()
+    }
+}
+
+impl<G> Visitable for &G
+where
+    G: Visitable,
+{
+    type Map = G::Map;
+
+    fn visit_map(&self) -> Self::Map {
+        (**self).visit_map()
+    }
+
+    fn reset_map(&self, map: &mut Self::Map) {
+        (**self).reset_map(map)
+    }
+}
+
+impl<N, E, Ty, S, W> GraphBase for Graph<N, E, Ty, S, W>
+where
+    N: NodeTrait,
+{
+    type NodeId = N;
+    type EdgeId = (N, N);
+}
+
+impl<N, E, Ty, S, W> Data for Graph<N, E, Ty, S, W>
+where
+    N: NodeTrait,
+{
+    type NodeWeight = N;
+    type EdgeWeight = E;
+}
+
+impl<N, E, Ty, S, W> NodeCount for Graph<N, E, Ty, S, W>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    fn node_count(&self) -> usize {
+        Graph::node_count(self)
+    }
+}
+
+impl<N, E, Ty, S, W> Visitable for Graph<N, E, Ty, S, W>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    type Map = HashSet<N>;
+
+    fn visit_map(&self) -> Self::Map {
+        HashSet::with_capacity(Graph::node_count(self))
+    }
+
+    fn reset_map(&self, map: &mut Self::Map) {
+        map.clear();
+    }
+}
+
+impl<N, E, Ty, S, W> GraphRef for &Graph<N, E, Ty, S, W> where N: NodeTrait {}
+
+impl<'a, N, E, Ty, S, W> IntoNeighbors for &'a Graph<N, E, Ty, S, W>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    type Neighbors = Neighbors<'a, N, Ty>;
+
+    fn neighbors(self, a: N) -> Self::Neighbors {
+        Graph::neighbors(self, a)
+    }
+}
+
+impl<'a, N, E, Ty, S, W> IntoNeighborsDirected for &'a Graph<N, E, Ty, S, W>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    type NeighborsDirected = NeighborsDirected<'a, N, Ty>;
+
+    fn neighbors_directed(self, a: N, dir: Direction) -> Self::NeighborsDirected {
+        Graph::neighbors_directed(self, a, dir)
+    }
+}
+
+impl<'a, N, E, Ty, S, W> IntoNodeIdentifiers for &'a Graph<N, E, Ty, S, W>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    type NodeIdentifiers = Nodes<'a, N, W>;
+
+    fn node_identifiers(self) -> Self::NodeIdentifiers {
+        self.nodes()
+    }
+}
+
+impl<'a, N, E, Ty, S, W> IntoEdgeReferences for &'a Graph<N, E, Ty, S, W>
+where
+    N: NodeTrait,
+    E: 'a,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    type EdgeRef = (N, N, &'a E);
+    type EdgeReferences = AllEdges<'a, N, E, Ty>;
+
+    fn edge_references(self) -> Self::EdgeReferences {
+        self.all_edges()
+    }
+}
+
+impl<'a, N, E, Ty, S, W> IntoEdges for &'a Graph<N, E, Ty, S, W>
+where
+    N: NodeTrait,
+    E: 'a,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    type Edges = Edges<'a, N, E, Ty, S>;
+
+    fn edges(self, a: N) -> Self::Edges {
+        Graph::edges(self, a)
+    }
+}
+
+impl<'a, N, E, Ty, S, W> IntoEdgesDirected for &'a Graph<N, E, Ty, S, W>
+where
+    N: NodeTrait,
+    E: 'a,
+    Ty: EdgeType,
+    S: BuildHasher,
+{
+    type EdgesDirected = EdgesDirected<'a, N, E, Ty, S>;
+
+    fn edges_directed(self, a: N, dir: Direction) -> Self::EdgesDirected {
+        Graph::edges_directed(self, a, dir)
+    }
+}
+
+impl<N, E, Ty> GraphBase for Csr<N, E, Ty>
+where
+    N: NodeTrait,
+{
+    type NodeId = N;
+    type EdgeId = (N, N);
+}
+
+impl<N, E, Ty> Data for Csr<N, E, Ty>
+where
+    N: NodeTrait,
+{
+    type NodeWeight = N;
+    type EdgeWeight = E;
+}
+
+impl<N, E, Ty> NodeCount for Csr<N, E, Ty>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+{
+    fn node_count(&self) -> usize {
+        Csr::node_count(self)
+    }
+}
+
+impl<N, E, Ty> Visitable for Csr<N, E, Ty>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+{
+    type Map = HashSet<N>;
+
+    fn visit_map(&self) -> Self::Map {
+        HashSet::with_capacity(Csr::node_count(self))
+    }
+
+    fn reset_map(&self, map: &mut Self::Map) {
+        map.clear();
+    }
+}
+
+impl<N, E, Ty> GraphRef for &Csr<N, E, Ty> where N: NodeTrait {}
+
+impl<'a, N, E, Ty> IntoNeighbors for &'a Csr<N, E, Ty>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+{
+    type Neighbors = CsrNeighbors<'a, N>;
+
+    fn neighbors(self, a: N) -> Self::Neighbors {
+        Csr::neighbors(self, a)
+    }
+}
+
+impl<'a, N, E, Ty> IntoNodeIdentifiers for &'a Csr<N, E, Ty>
+where
+    N: NodeTrait,
+    Ty: EdgeType,
+{
+    type NodeIdentifiers = Cloned<slice::Iter<'a, N>>;
+
+    fn node_identifiers(self) -> Self::NodeIdentifiers {
+        self.nodes().iter().cloned()
+    }
+}
+
+impl<'a, N, E, Ty> IntoEdgeReferences for &'a Csr<N, E, Ty>
+where
+    N: NodeTrait,
+    E: 'a,
+    Ty: EdgeType,
+{
+    type EdgeRef = (N, N, &'a E);
+    type EdgeReferences = CsrAllEdges<'a, N, E, Ty>;
+
+    fn edge_references(self) -> Self::EdgeReferences {
+        self.all_edges()
+    }
+}
+
+impl<'a, N, E, Ty> IntoEdges for &'a Csr<N, E, Ty>
+where
+    N: NodeTrait,
+    E: 'a,
+    Ty: EdgeType,
+{
+    type Edges = CsrEdges<'a, N, E>;
+
+    fn edges(self, a: N) -> Self::Edges {
+        Csr::edges(self, a)
+    }
+}
+
+/// Depth first search walker.
+///
+/// The walker doesn't borrow the graph, so the graph can be modified between the steps.
+///
+/// # Examples
+///
+/// ```
+/// use safe_graph::visit::Dfs;
+/// use safe_graph::Graph;
+///
+/// let graph = Graph::<_, ()>::from_edges(&[(1, 2), (1, 3), (2, 4)]);
+/// let mut dfs = Dfs::new(&graph, 1);
+/// let mut order = Vec::new();
+///
+/// while let Some(n) = dfs.next(&graph) {
+///     order.push(n);
+/// }
+///
+/// assert_eq!(order, vec![1, 3, 2, 4]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Dfs<N, VM> {
+    /// The stack of nodes to visit.
+    pub stack: Vec<N>,
+    /// The map of discovered nodes.
+    pub discovered: VM,
+}
+
+impl<N, VM> Dfs<N, VM>
+where
+    N: Copy + PartialEq,
+    VM: VisitMap<N>,
+{
+    /// Create a new `Dfs` starting at node `start`.
+    pub fn new<G>(graph: G, start: N) -> Self
+    where
+        G: GraphRef + Visitable<NodeId = N, Map = VM>,
+    {
+        let mut dfs = Self::empty(graph);
+        dfs.move_to(start);
+        dfs
+    }
+
+    /// Create a new `Dfs` with an empty stack.
+    pub fn empty<G>(graph: G) -> Self
+    where
+        G: GraphRef + Visitable<NodeId = N, Map = VM>,
+    {
+        Self {
+            stack: Vec::new(),
+            discovered: graph.visit_map(),
+        }
+    }
+
+    /// Keep the discovered map, but clear the stack and restart at node `start`.
+    pub fn move_to(&mut self, start: N) {
+        self.stack.clear();
+        self.stack.push(start);
+    }
+
+    /// Return the next node in the depth first order, or `None` if the traversal is done.
+    pub fn next<G>(&mut self, graph: G) -> Option<N>
+    where
+        G: IntoNeighbors<NodeId = N>,
+    {
+        while let Some(n) = self.stack.pop() {
+            if self.discovered.visit(n) {
+                for succ in graph.neighbors(n) {
+                    if !self.discovered.is_visited(&succ) {
+                        self.stack.push(succ);
+                    }
+                }
+
+                return Some(n);
+            }
+        }
+
+        None
+    }
+}
+
+/// Breadth first search walker.
+///
+/// The walker doesn't borrow the graph, so the graph can be modified between the steps.
+#[derive(Clone, Debug)]
+pub struct Bfs<N, VM> {
+    /// The queue of nodes to visit.
+    pub queue: VecDeque<N>,
+    /// The map of discovered nodes.
+    pub discovered: VM,
+}
+
+impl<N, VM> Bfs<N, VM>
+where
+    N: Copy + PartialEq,
+    VM: VisitMap<N>,
+{
+    /// Create a new `Bfs` starting at node `start`.
+    pub fn new<G>(graph: G, start: N) -> Self
+    where
+        G: GraphRef + Visitable<NodeId = N, Map = VM>,
+    {
+        let mut discovered = graph.visit_map();
+        discovered.visit(start);
+        let mut queue = VecDeque::new();
+        queue.push_back(start);
+
+        Self { queue, discovered }
+    }
+
+    /// Return the next node in the breadth first order, or `None` if the traversal is done.
+    pub fn next<G>(&mut self, graph: G) -> Option<N>
+    where
+        G: IntoNeighbors<NodeId = N>,
+    {
+        let n = self.queue.pop_front()?;
+        for succ in graph.neighbors(n) {
+            if self.discovered.visit(succ) {
+                self.queue.push_back(succ);
+            }
+        }
+
+        Some(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edge::Direction::Incoming;
+    use crate::graph::{Graph, Undirected};
+    use crate::visit::{
+        Bfs, Dfs, EdgeRef, IntoEdgeReferences, IntoEdges, IntoNeighbors, IntoNeighborsDirected,
+        IntoNodeIdentifiers, NodeCount, VisitMap, Visitable,
+    };
+
+    /// Count nodes reachable from `start` using the generic traits only.
+    fn reachable<G>(graph: G, start: G::NodeId) -> usize
+    where
+        G: IntoNeighbors + Visitable,
+    {
+        let mut dfs = Dfs::new(graph, start);
+        let mut count = 0;
+        while dfs.next(graph).is_some() {
+            count += 1;
+        }
+        count
+    }
+
+    #[test]
+    fn graph_traits() {
+        let graph = Graph::<u32, f32>::from_edges(&[(1, 2, 0.5), (3, 2, 1.5)]);
+        let g = &graph;
+
+        assert_eq!(NodeCount::node_count(&g), 3);
+        assert_eq!(g.node_identifiers().collect::<Vec<_>>(), vec![1, 2, 3]);
+        assert_eq!(IntoNeighbors::neighbors(g, 1).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(
+            IntoNeighborsDirected::neighbors_directed(g, 2, Incoming).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+
+        let edges: Vec<_> = g
+            .edge_references()
+            .map(|e| (e.source(), e.target(), *e.weight()))
+            .collect();
+        assert_eq!(edges, vec![(1, 2, 0.5), (3, 2, 1.5)]);
+        assert_eq!(IntoEdges::edges(g, 2).count(), 0);
+    }
+
+    #[test]
+    fn visit_map() {
+        let graph = Graph::<u32, ()>::from_edges(&[(1, 2)]);
+        let mut map = graph.visit_map();
+
+        assert!(map.visit(1));
+        assert!(!map.visit(1));
+        assert!(map.is_visited(&1));
+        assert!(!map.is_visited(&2));
+
+        graph.reset_map(&mut map);
+
+        assert!(!map.is_visited(&1));
+    }
+
+    #[test]
+    fn dfs() {
+        let graph = Graph::<u32, ()>::from_edges(&[(1, 2), (1, 3), (2, 4), (3, 4), (5, 1)]);
+        let mut dfs = Dfs::new(&graph, 1);
+        let mut order = Vec::new();
+        while let Some(n) = dfs.next(&graph) {
+            order.push(n);
+        }
+
+        assert_eq!(order, vec![1, 3, 4, 2]);
+
+        // Continue from another node keeping the discovered nodes.
+        dfs.move_to(5);
+
+        assert_eq!(dfs.next(&graph), Some(5));
+        assert_eq!(dfs.next(&graph), None);
+    }
+
+    #[test]
+    fn bfs() {
+        let graph =
+            Graph::<u32, (), Undirected>::from_edges(&[(1, 2), (1, 3), (2, 4), (3, 4), (4, 5)]);
+        let mut bfs = Bfs::new(&graph, 1);
+        let mut order = Vec::new();
+        while let Some(n) = bfs.next(&graph) {
+            order.push(n);
+        }
+
+        assert_eq!(order, vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn generic_algorithm() {
+        let graph = Graph::<u32, ()>::from_edges(&[(1, 2), (2, 3), (4, 1)]);
+
+        assert_eq!(reachable(&graph, 1), 3);
+        assert_eq!(reachable(&graph, 4), 4);
+        assert_eq!(reachable(&graph, 3), 1);
+    }
+
+    #[test]
+    fn csr_traits() {
+        let graph = Graph::<u32, f32>::from_edges(&[(1, 2, 0.5), (2, 3, 1.5), (4, 1, 2.5)]);
+        let csr = graph.to_csr();
+
+        assert_eq!(reachable(&csr, 1), 3);
+        assert_eq!(reachable(&csr, 4), 4);
+        assert_eq!(NodeCount::node_count(&&csr), 4);
+        assert_eq!(
+            (&csr).node_identifiers().collect::<Vec<_>>(),
+            vec![1, 2, 3, 4]
+        );
+        assert_eq!(
+            (&csr).edge_references().collect::<Vec<_>>(),
+            vec![(1, 2, &0.5), (2, 3, &1.5), (4, 1, &2.5)]
+        );
+        assert_eq!(
+            IntoEdges::edges(&csr, 2).collect::<Vec<_>>(),
+            vec![(2, 3, &1.5)]
+        );
+    }
+}
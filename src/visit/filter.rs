@@ -0,0 +1,573 @@
+//! Node and edge filtering graph adaptors.
+
+use crate::edge::Direction::{self, Incoming, Outgoing};
+use crate::visit::{
+    Data, EdgeRef, GraphBase, GraphRef, IntoEdgeReferences, IntoEdges, IntoEdgesDirected,
+    IntoNeighbors, IntoNeighborsDirected, IntoNodeIdentifiers, Visitable,
+};
+use std::collections::HashSet;
+use std::hash::{BuildHasher, Hash};
+
+/// A filter deciding which nodes are part of a `NodeFiltered` graph.
+pub trait FilterNode<N> {
+    /// Return `true` if node `n` should be included.
+    fn include_node(&self, n: N) -> bool;
+}
+
+impl<F, N> FilterNode<N> for F
+where
+    F: Fn(N) -> bool,
+{
+    fn include_node(&self, n: N) -> bool {
+        (*self)(n)
+    }
+}
+
+/// Include the nodes contained in the set.
+impl<N, S> FilterNode<N> for HashSet<N, S>
+where
+    N: Eq + Hash,
+    S: BuildHasher,
+{
+    fn include_node(&self, n: N) -> bool {
+        self.contains(&n)
+    }
+}
+
+/// A node-filtering graph adaptor.
+///
+/// Nodes not accepted by the filter are hidden together with all their edges. The adaptor
+/// wraps a graph reference, the traversal traits are implemented for `&NodeFiltered`.
+///
+/// `NodeCount` is not implemented, as counting the accepted nodes means running the filter on
+/// every node. Use `node_identifiers().count()` instead.
+///
+/// # Examples
+///
+/// ```
+/// use safe_graph::visit::{Dfs, NodeFiltered};
+/// use safe_graph::Graph;
+///
+/// let graph = Graph::<_, ()>::from_edges(&[(1, 2), (2, 3), (1, 4), (4, 5)]);
+/// // Node `4` is not active.
+/// let active = NodeFiltered::from_fn(&graph, |n| n != 4);
+///
+/// let mut dfs = Dfs::new(&active, 1);
+/// let mut reachable = Vec::new();
+/// while let Some(n) = dfs.next(&active) {
+///     reachable.push(n);
+/// }
+///
+/// assert_eq!(reachable, vec![1, 2, 3]);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct NodeFiltered<G, F>(pub G, pub F);
+
+impl<G, F> NodeFiltered<G, F>
+where
+    G: GraphBase,
+{
+    /// Create a new `NodeFiltered` including the nodes accepted by the closure.
+    pub fn from_fn(graph: G, filter: F) -> Self
+    where
+        F: Fn(G::NodeId) -> bool,
+    {
+        NodeFiltered(graph, filter)
+    }
+}
+
+impl<G, F> GraphBase for NodeFiltered<G, F>
+where
+    G: GraphBase,
+{
+    type NodeId = G::NodeId;
+    type EdgeId = G::EdgeId;
+}
+
+impl<G, F> Data for NodeFiltered<G, F>
+where
+    G: Data,
+{
+    type NodeWeight = G::NodeWeight;
+    type EdgeWeight = G::EdgeWeight;
+}
+
+impl<G, F> Visitable for NodeFiltered<G, F>
+where
+    G: Visitable,
+{
+    type Map = G::Map;
+
+    fn visit_map(&self) -> Self::Map {
+        self.0.visit_map()
+    }
+
+    fn reset_map(&self, map: &mut Self::Map) {
+        self.0.reset_map(map)
+    }
+}
+
+impl<G, F> GraphRef for &NodeFiltered<G, F> where G: GraphBase {}
+
+impl<'a, G, F> IntoNeighbors for &'a NodeFiltered<G, F>
+where
+    G: IntoNeighbors,
+    F: FilterNode<G::NodeId>,
+{
+    type Neighbors = NodeFilteredNeighbors<'a, G::Neighbors, F>;
+
+    fn neighbors(self, a: Self::NodeId) -> Self::Neighbors {
+        NodeFilteredNeighbors {
+            include_source: self.1.include_node(a),
+            iter: self.0.neighbors(a),
+            filter: &self.1,
+        }
+    }
+}
+
+impl<'a, G, F> IntoNeighborsDirected for &'a NodeFiltered<G, F>
+where
+    G: IntoNeighborsDirected,
+    F: FilterNode<G::NodeId>,
+{
+    type NeighborsDirected = NodeFilteredNeighbors<'a, G::NeighborsDirected, F>;
+
+    fn neighbors_directed(self, a: Self::NodeId, dir: Direction) -> Self::NeighborsDirected {
+        NodeFilteredNeighbors {
+            include_source: self.1.include_node(a),
+            iter: self.0.neighbors_directed(a, dir),
+            filter: &self.1,
+        }
+    }
+}
+
+impl<'a, G, F> IntoNodeIdentifiers for &'a NodeFiltered<G, F>
+where
+    G: IntoNodeIdentifiers,
+    F: FilterNode<G::NodeId>,
+{
+    type NodeIdentifiers = NodeFilteredNodes<'a, G::NodeIdentifiers, F>;
+
+    fn node_identifiers(self) -> Self::NodeIdentifiers {
+        NodeFilteredNodes {
+            iter: self.0.node_identifiers(),
+            filter: &self.1,
+        }
+    }
+}
+
+impl<'a, G, F> IntoEdgeReferences for &'a NodeFiltered<G, F>
+where
+    G: IntoEdgeReferences,
+    F: FilterNode<G::NodeId>,
+{
+    type EdgeRef = G::EdgeRef;
+    type EdgeReferences = NodeFilteredEdges<'a, G::EdgeReferences, F>;
+
+    fn edge_references(self) -> Self::EdgeReferences {
+        NodeFilteredEdges {
+            include_source: true,
+            iter: self.0.edge_references(),
+            filter: &self.1,
+        }
+    }
+}
+
+impl<'a, G, F> IntoEdges for &'a NodeFiltered<G, F>
+where
+    G: IntoEdges,
+    F: FilterNode<G::NodeId>,
+{
+    type Edges = NodeFilteredEdges<'a, G::Edges, F>;
+
+    fn edges(self, a: Self::NodeId) -> Self::Edges {
+        NodeFilteredEdges {
+            include_source: self.1.include_node(a),
+            iter: self.0.edges(a),
+            filter: &self.1,
+        }
+    }
+}
+
+/// Iterator of the nodes accepted by a node filter.
+pub struct NodeFilteredNodes<'a, I, F: 'a> {
+    iter: I,
+    filter: &'a F,
+}
+
+impl<'a, I, F> Iterator for NodeFilteredNodes<'a, I, F>
+where
+    I: Iterator,
+    I::Item: Copy,
+    F: FilterNode<I::Item>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let filter = self.filter;
+        self.iter.find(|&n| filter.include_node(n))
+    }
+}
+
+/// Iterator of the neighbors accepted by a node filter, empty if the node itself is not
+/// accepted.
+pub struct NodeFilteredNeighbors<'a, I, F: 'a> {
+    include_source: bool,
+    iter: I,
+    filter: &'a F,
+}
+
+impl<'a, I, F> Iterator for NodeFilteredNeighbors<'a, I, F>
+where
+    I: Iterator,
+    I::Item: Copy,
+    F: FilterNode<I::Item>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.include_source {
+            return None;
+        }
+
+        let filter = self.filter;
+        self.iter.find(|&n| filter.include_node(n))
+    }
+}
+
+/// Iterator of the edges having both nodes accepted by a node filter.
+pub struct NodeFilteredEdges<'a, I, F: 'a> {
+    include_source: bool,
+    iter: I,
+    filter: &'a F,
+}
+
+impl<'a, I, F> Iterator for NodeFilteredEdges<'a, I, F>
+where
+    I: Iterator,
+    I::Item: EdgeRef,
+    F: FilterNode<<I::Item as EdgeRef>::NodeId>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if !self.include_source {
+            return None;
+        }
+
+        let filter = self.filter;
+        self.iter
+            .find(|e| filter.include_node(e.source()) && filter.include_node(e.target()))
+    }
+}
+
+/// A filter deciding which edges are part of an `EdgeFiltered` graph.
+pub trait FilterEdge<Edge> {
+    /// Return `true` if the edge should be included.
+    fn include_edge(&self, edge: Edge) -> bool;
+}
+
+impl<F, Edge> FilterEdge<Edge> for F
+where
+    F: Fn(Edge) -> bool,
+{
+    fn include_edge(&self, edge: Edge) -> bool {
+        (*self)(edge)
+    }
+}
+
+/// An edge-filtering graph adaptor.
+///
+/// Edges not accepted by the filter are hidden, all nodes are kept. The adaptor wraps a graph
+/// reference, the traversal traits are implemented for `&EdgeFiltered`.
+///
+/// # Examples
+///
+/// ```
+/// use safe_graph::visit::{Dfs, EdgeFiltered};
+/// use safe_graph::Graph;
+///
+/// let graph = Graph::<_, _>::from_edges(&[(1, 2, 0.9), (2, 3, 0.1), (1, 4, 0.6)]);
+/// let strong = EdgeFiltered::from_fn(&graph, |(_, _, &weight)| weight > 0.5);
+///
+/// let mut dfs = Dfs::new(&strong, 1);
+/// let mut reachable = Vec::new();
+/// while let Some(n) = dfs.next(&strong) {
+///     reachable.push(n);
+/// }
+///
+/// assert_eq!(reachable, vec![1, 4, 2]);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct EdgeFiltered<G, F>(pub G, pub F);
+
+impl<G, F> EdgeFiltered<G, F>
+where
+    G: IntoEdgeReferences,
+{
+    /// Create a new `EdgeFiltered` including the edges accepted by the closure.
+    pub fn from_fn(graph: G, filter: F) -> Self
+    where
+        F: Fn(G::EdgeRef) -> bool,
+    {
+        EdgeFiltered(graph, filter)
+    }
+}
+
+impl<G, F> GraphBase for EdgeFiltered<G, F>
+where
+    G: GraphBase,
+{
+    type NodeId = G::NodeId;
+    type EdgeId = G::EdgeId;
+}
+
+impl<G, F> Data for EdgeFiltered<G, F>
+where
+    G: Data,
+{
+    type NodeWeight = G::NodeWeight;
+    type EdgeWeight = G::EdgeWeight;
+}
+
+impl<G, F> Visitable for EdgeFiltered<G, F>
+where
+    G: Visitable,
+{
+    type Map = G::Map;
+
+    fn visit_map(&self) -> Self::Map {
+        self.0.visit_map()
+    }
+
+    fn reset_map(&self, map: &mut Self::Map) {
+        self.0.reset_map(map)
+    }
+}
+
+impl<G, F> GraphRef for &EdgeFiltered<G, F> where G: GraphBase {}
+
+impl<'a, G, F> IntoNeighbors for &'a EdgeFiltered<G, F>
+where
+    G: IntoEdges,
+    F: FilterEdge<G::EdgeRef>,
+{
+    type Neighbors = EdgeFilteredNeighbors<'a, G::Edges, F>;
+
+    fn neighbors(self, a: Self::NodeId) -> Self::Neighbors {
+        EdgeFilteredNeighbors {
+            iter: self.0.edges(a),
+            filter: &self.1,
+        }
+    }
+}
+
+impl<'a, G, F> IntoNeighborsDirected for &'a EdgeFiltered<G, F>
+where
+    G: IntoEdgesDirected,
+    F: FilterEdge<G::EdgeRef>,
+{
+    type NeighborsDirected = EdgeFilteredNeighborsDirected<'a, G::EdgesDirected, F>;
+
+    fn neighbors_directed(self, a: Self::NodeId, dir: Direction) -> Self::NeighborsDirected {
+        EdgeFilteredNeighborsDirected {
+            iter: self.0.edges_directed(a, dir),
+            dir,
+            filter: &self.1,
+        }
+    }
+}
+
+impl<G, F> IntoNodeIdentifiers for &EdgeFiltered<G, F>
+where
+    G: IntoNodeIdentifiers,
+{
+    type NodeIdentifiers = G::NodeIdentifiers;
+
+    fn node_identifiers(self) -> Self::NodeIdentifiers {
+        self.0.node_identifiers()
+    }
+}
+
+impl<'a, G, F> IntoEdgeReferences for &'a EdgeFiltered<G, F>
+where
+    G: IntoEdgeReferences,
+    F: FilterEdge<G::EdgeRef>,
+{
+    type EdgeRef = G::EdgeRef;
+    type EdgeReferences = EdgeFilteredEdges<'a, G::EdgeReferences, F>;
+
+    fn edge_references(self) -> Self::EdgeReferences {
+        EdgeFilteredEdges {
+            iter: self.0.edge_references(),
+            filter: &self.1,
+        }
+    }
+}
+
+impl<'a, G, F> IntoEdges for &'a EdgeFiltered<G, F>
+where
+    G: IntoEdges,
+    F: FilterEdge<G::EdgeRef>,
+{
+    type Edges = EdgeFilteredEdges<'a, G::Edges, F>;
+
+    fn edges(self, a: Self::NodeId) -> Self::Edges {
+        EdgeFilteredEdges {
+            iter: self.0.edges(a),
+            filter: &self.1,
+        }
+    }
+}
+
+/// Iterator of the neighbors connected by edges accepted by an edge filter.
+pub struct EdgeFilteredNeighbors<'a, I, F: 'a> {
+    iter: I,
+    filter: &'a F,
+}
+
+impl<'a, I, F> Iterator for EdgeFilteredNeighbors<'a, I, F>
+where
+    I: Iterator,
+    I::Item: EdgeRef,
+    F: FilterEdge<I::Item>,
+{
+    type Item = <I::Item as EdgeRef>::NodeId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let filter = self.filter;
+        self.iter
+            .find(|&e| filter.include_edge(e))
+            .map(|e| e.target())
+    }
+}
+
+/// Iterator of the neighbors in a given direction connected by edges accepted by an edge
+/// filter.
+pub struct EdgeFilteredNeighborsDirected<'a, I, F: 'a> {
+    iter: I,
+    dir: Direction,
+    filter: &'a F,
+}
+
+impl<'a, I, F> Iterator for EdgeFilteredNeighborsDirected<'a, I, F>
+where
+    I: Iterator,
+    I::Item: EdgeRef,
+    F: FilterEdge<I::Item>,
+{
+    type Item = <I::Item as EdgeRef>::NodeId;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let filter = self.filter;
+        let e = self.iter.find(|&e| filter.include_edge(e))?;
+
+        match self.dir {
+            Outgoing => Some(e.target()),
+            Incoming => Some(e.source()),
+        }
+    }
+}
+
+/// Iterator of the edges accepted by an edge filter.
+pub struct EdgeFilteredEdges<'a, I, F: 'a> {
+    iter: I,
+    filter: &'a F,
+}
+
+impl<'a, I, F> Iterator for EdgeFilteredEdges<'a, I, F>
+where
+    I: Iterator,
+    I::Item: EdgeRef,
+    F: FilterEdge<I::Item>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let filter = self.filter;
+        self.iter.find(|&e| filter.include_edge(e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edge::Direction::{Incoming, Outgoing};
+    use crate::graph::{Graph, Undirected};
+    use crate::visit::{
+        Bfs, EdgeFiltered, IntoEdgeReferences, IntoEdges, IntoNeighbors, IntoNeighborsDirected,
+        IntoNodeIdentifiers, NodeFiltered, Reversed,
+    };
+    use std::collections::HashSet;
+
+    #[test]
+    fn node_filtered_fn() {
+        let graph = Graph::<u32, f32>::from_edges(&[(1, 2, 0.5), (1, 3, 1.5), (3, 2, 2.5)]);
+        let filtered = NodeFiltered::from_fn(&graph, |n| n != 3);
+
+        assert_eq!(filtered.neighbors(1).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(filtered.neighbors(3).count(), 0);
+        assert_eq!(
+            filtered.neighbors_directed(2, Incoming).collect::<Vec<_>>(),
+            vec![1]
+        );
+        assert_eq!(filtered.node_identifiers().collect::<Vec<_>>(), vec![1, 2]);
+        assert_eq!(
+            filtered.edge_references().collect::<Vec<_>>(),
+            vec![(1, 2, &0.5)]
+        );
+        assert_eq!(filtered.edges(1).collect::<Vec<_>>(), vec![(1, 2, &0.5)]);
+    }
+
+    #[test]
+    fn node_filtered_set() {
+        let graph = Graph::<u32, (), Undirected>::from_edges(&[(1, 2), (2, 3), (3, 4)]);
+        let active: HashSet<u32> = [1, 2, 4].iter().cloned().collect();
+        let filtered = NodeFiltered(&graph, active);
+
+        let mut bfs = Bfs::new(&filtered, 1);
+        let mut order = Vec::new();
+        while let Some(n) = bfs.next(&filtered) {
+            order.push(n);
+        }
+
+        assert_eq!(order, vec![1, 2]);
+    }
+
+    #[test]
+    fn edge_filtered() {
+        let graph = Graph::<u32, u32>::from_edges(&[(1, 2, 5), (1, 3, 1), (3, 2, 7)]);
+        let filtered = EdgeFiltered::from_fn(&graph, |(_, _, &w): (u32, u32, &u32)| w > 2);
+
+        assert_eq!(filtered.neighbors(1).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(filtered.neighbors(3).collect::<Vec<_>>(), vec![2]);
+        assert_eq!(filtered.node_identifiers().count(), 3);
+        assert_eq!(
+            filtered.edge_references().collect::<Vec<_>>(),
+            vec![(1, 2, &5), (3, 2, &7)]
+        );
+        assert_eq!(filtered.edges(1).collect::<Vec<_>>(), vec![(1, 2, &5)]);
+        assert_eq!(
+            filtered.neighbors_directed(2, Incoming).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+        assert_eq!(
+            filtered.neighbors_directed(1, Outgoing).collect::<Vec<_>>(),
+            vec![2]
+        );
+    }
+
+    #[test]
+    fn composed_adaptors() {
+        let graph = Graph::<u32, u32>::from_edges(&[(1, 2, 5), (3, 2, 1), (4, 2, 7)]);
+        let filtered = NodeFiltered::from_fn(Reversed(&graph), |n| n != 4);
+
+        assert_eq!(filtered.neighbors(2).collect::<Vec<_>>(), vec![1, 3]);
+
+        // Reversing an edge filtered graph.
+        let strong = EdgeFiltered::from_fn(&graph, |(_, _, &w): (u32, u32, &u32)| w > 2);
+        let reversed = Reversed(&strong);
+
+        assert_eq!(reversed.neighbors(2).collect::<Vec<_>>(), vec![1, 4]);
+        assert_eq!(reversed.neighbors(3).count(), 0);
+    }
+}
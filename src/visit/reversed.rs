@@ -0,0 +1,295 @@
+//! Reversed graph adaptor.
+
+use crate::edge::Direction::{self, Incoming};
+use crate::visit::{
+    Data, GraphBase, GraphRef, IntoEdgeReferences, IntoEdges, IntoEdgesDirected, IntoNeighbors,
+    IntoNeighborsDirected, IntoNodeIdentifiers, NodeCount, Visitable,
+};
+
+/// An edge-reversing graph adaptor.
+///
+/// All edges have the opposite direction, i.e. `neighbors` yields the `Incoming` neighbors of
+/// the wrapped graph and every edge `(a, b)` is reported as `(b, a)`. The adaptor is zero-copy,
+/// it only wraps a graph reference like `&Graph`.
+///
+/// # Examples
+///
+/// ```
+/// use safe_graph::visit::{Dfs, Reversed};
+/// use safe_graph::Graph;
+///
+/// // `1` depends on `2` and `2` depends on `3`.
+/// let graph = Graph::<_, ()>::from_edges(&[(1, 2), (2, 3)]);
+/// let reversed = Reversed(&graph);
+///
+/// // Who depends on `3`, directly or transitively?
+/// let mut dfs = Dfs::new(reversed, 3);
+/// let mut dependents = Vec::new();
+/// while let Some(n) = dfs.next(reversed) {
+///     dependents.push(n);
+/// }
+///
+/// assert_eq!(dependents, vec![3, 2, 1]);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Reversed<G>(pub G);
+
+impl<G> GraphBase for Reversed<G>
+where
+    G: GraphBase,
+{
+    type NodeId = G::NodeId;
+    type EdgeId = G::EdgeId;
+}
+
+impl<G> GraphRef for Reversed<G> where G: GraphRef {}
+
+impl<G> Data for Reversed<G>
+where
+    G: Data,
+{
+    type NodeWeight = G::NodeWeight;
+    type EdgeWeight = G::EdgeWeight;
+}
+
+impl<G> NodeCount for Reversed<G>
+where
+    G: NodeCount,
+{
+    fn node_count(&self) -> usize {
+        self.0.node_count()
+    }
+}
+
+impl<G> Visitable for Reversed<G>
+where
+    G: Visitable,
+{
+    type Map = G::Map;
+
+    fn visit_map(&self) -> Self::Map {
+        self.0.visit_map()
+    }
+
+    fn reset_map(&self, map: &mut Self::Map) {
+        self.0.reset_map(map)
+    }
+}
+
+impl<G> IntoNodeIdentifiers for Reversed<G>
+where
+    G: IntoNodeIdentifiers,
+{
+    type NodeIdentifiers = G::NodeIdentifiers;
+
+    fn node_identifiers(self) -> Self::NodeIdentifiers {
+        self.0.node_identifiers()
+    }
+}
+
+impl<G> IntoNeighbors for Reversed<G>
+where
+    G: IntoNeighborsDirected,
+{
+    type Neighbors = G::NeighborsDirected;
+
+    fn neighbors(self, a: Self::NodeId) -> Self::Neighbors {
+        self.0.neighbors_directed(a, Incoming)
+    }
+}
+
+impl<G> IntoNeighborsDirected for Reversed<G>
+where
+    G: IntoNeighborsDirected,
+{
+    type NeighborsDirected = G::NeighborsDirected;
+
+    fn neighbors_directed(self, a: Self::NodeId, dir: Direction) -> Self::NeighborsDirected {
+        self.0.neighbors_directed(a, dir.opposite())
+    }
+}
+
+/// Iterator over all edges of a reversed graph.
+///
+/// Iterator element type is `(N, N, &E)`, the edge `(a, b)` being reported as `(b, a)`.
+pub struct ReversedEdgeReferences<I> {
+    iter: I,
+}
+
+impl<'a, I, N, E> Iterator for ReversedEdgeReferences<I>
+where
+    I: Iterator<Item = (N, N, &'a E)>,
+    E: 'a,
+{
+    type Item = (N, N, &'a E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(a, b, weight)| (b, a, weight))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, G, N, E> IntoEdgeReferences for Reversed<G>
+where
+    G: IntoEdgeReferences<NodeId = N, EdgeWeight = E, EdgeRef = (N, N, &'a E)>,
+    N: Copy,
+    E: 'a,
+{
+    type EdgeRef = (N, N, &'a E);
+    type EdgeReferences = ReversedEdgeReferences<G::EdgeReferences>;
+
+    fn edge_references(self) -> Self::EdgeReferences {
+        ReversedEdgeReferences {
+            iter: self.0.edge_references(),
+        }
+    }
+}
+
+/// Iterator over the edges of a node of a reversed graph.
+///
+/// Iterator element type is `(N, N, &E)`, the edge `(a, b)` being reported as `(b, a)`.
+pub struct ReversedEdges<I> {
+    iter: I,
+}
+
+impl<'a, I, N, E> Iterator for ReversedEdges<I>
+where
+    I: Iterator<Item = (N, N, &'a E)>,
+    E: 'a,
+{
+    type Item = (N, N, &'a E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|(a, b, weight)| (b, a, weight))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, G, N, E> IntoEdges for Reversed<G>
+where
+    G: IntoEdgesDirected<NodeId = N, EdgeWeight = E, EdgeRef = (N, N, &'a E)>,
+    N: Copy,
+    E: 'a,
+{
+    type Edges = ReversedEdges<G::EdgesDirected>;
+
+    fn edges(self, a: Self::NodeId) -> Self::Edges {
+        ReversedEdges {
+            iter: self.0.edges_directed(a, Incoming),
+        }
+    }
+}
+
+impl<'a, G, N, E> IntoEdgesDirected for Reversed<G>
+where
+    G: IntoEdgesDirected<NodeId = N, EdgeWeight = E, EdgeRef = (N, N, &'a E)>,
+    N: Copy,
+    E: 'a,
+{
+    type EdgesDirected = ReversedEdges<G::EdgesDirected>;
+
+    fn edges_directed(self, a: Self::NodeId, dir: Direction) -> Self::EdgesDirected {
+        ReversedEdges {
+            iter: self.0.edges_directed(a, dir.opposite()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edge::Direction::{Incoming, Outgoing};
+    use crate::graph::Graph;
+    use crate::visit::{
+        Bfs, EdgeFiltered, EdgeRef, IntoEdgeReferences, IntoEdges, IntoEdgesDirected,
+        IntoNeighbors, IntoNeighborsDirected, IntoNodeIdentifiers, NodeCount, Reversed,
+    };
+
+    #[test]
+    fn neighbors() {
+        let graph = Graph::<u32, ()>::from_edges(&[(1, 2), (3, 2), (2, 4)]);
+        let reversed = Reversed(&graph);
+
+        assert_eq!(reversed.neighbors(2).collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(reversed.neighbors(1).count(), 0);
+        assert_eq!(
+            reversed.neighbors_directed(2, Incoming).collect::<Vec<_>>(),
+            vec![4]
+        );
+        assert_eq!(
+            reversed.neighbors_directed(2, Outgoing).collect::<Vec<_>>(),
+            vec![1, 3]
+        );
+    }
+
+    #[test]
+    fn edge_references() {
+        let graph = Graph::<u32, f32>::from_edges(&[(1, 2, 0.5), (2, 3, 1.5)]);
+        let edges: Vec<_> = Reversed(&graph).edge_references().collect();
+
+        assert_eq!(edges, vec![(2, 1, &0.5), (3, 2, &1.5)]);
+
+        // Generic access through `EdgeRef`.
+        let sources: Vec<_> = Reversed(Reversed(&graph))
+            .edge_references()
+            .map(|e| e.source())
+            .collect();
+
+        assert_eq!(sources, vec![1, 2]);
+    }
+
+    #[test]
+    fn edges() {
+        let graph = Graph::<u32, u32>::from_edges(&[(1, 2, 5), (3, 2, 7), (2, 4, 9)]);
+        let reversed = Reversed(&graph);
+
+        assert_eq!(
+            reversed.edges(2).collect::<Vec<_>>(),
+            vec![(2, 1, &5), (2, 3, &7)]
+        );
+        assert_eq!(
+            reversed.edges_directed(2, Incoming).collect::<Vec<_>>(),
+            vec![(4, 2, &9)]
+        );
+
+        // Edge filters work on top of the reversed graph.
+        let filtered = EdgeFiltered::from_fn(reversed, |(_, _, &w): (u32, u32, &u32)| w > 5);
+
+        assert_eq!((&filtered).neighbors(2).collect::<Vec<_>>(), vec![3]);
+    }
+
+    #[test]
+    fn nodes() {
+        let graph = Graph::<u32, ()>::from_edges(&[(1, 2), (3, 2)]);
+        let reversed = Reversed(&graph);
+
+        assert_eq!(reversed.node_count(), 3);
+        assert_eq!(
+            reversed.node_identifiers().collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+    }
+
+    #[test]
+    fn bfs() {
+        let graph = Graph::<u32, ()>::from_edges(&[(1, 2), (2, 3), (4, 3), (3, 5)]);
+        let reversed = Reversed(&graph);
+        let mut bfs = Bfs::new(reversed, 3);
+        let mut order = Vec::new();
+        while let Some(n) = bfs.next(reversed) {
+            order.push(n);
+        }
+
+        assert_eq!(order, vec![3, 2, 4, 1]);
+
+        // Reversing twice gives the original graph.
+        let twice = Reversed(reversed);
+
+        assert_eq!(twice.neighbors(3).collect::<Vec<_>>(), vec![5]);
+    }
+}
@@ -0,0 +1,290 @@
+//! Undirected view of a directed graph.
+
+use crate::edge::{
+    AllEdges,
+    Direction::{self, Incoming, Outgoing},
+};
+use crate::graph::{Directed, Graph};
+use crate::node::NodeTrait;
+use crate::visit::{
+    Data, GraphBase, GraphRef, IntoEdgeReferences, IntoEdges, IntoNeighbors, IntoNeighborsDirected,
+    IntoNodeIdentifiers, NodeCount, Visitable,
+};
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+use std::iter::Enumerate;
+use std::slice::Iter;
+
+/// A graph adaptor ignoring the direction of edges.
+///
+/// Both `Outgoing` and `Incoming` neighbors of a node are its neighbors. A pair of reciprocal
+/// edges `(a, b)` and `(b, a)` is seen as a single edge, having the weight of the edge with the
+/// lower edge index. That's the edge inserted first, unless `remove_edge` has since moved the
+/// last edge into the index of a removed one. The adaptor is zero-copy, it wraps a
+/// `&Graph<N, E, Directed>`.
+///
+/// # Examples
+///
+/// ```
+/// use safe_graph::visit::{AsUndirected, Dfs};
+/// use safe_graph::Graph;
+///
+/// let graph = Graph::<_, ()>::from_edges(&[(1, 2), (3, 2), (4, 5)]);
+/// let undirected = AsUndirected(&graph);
+///
+/// // Nodes weakly connected to `1`.
+/// let mut dfs = Dfs::new(undirected, 1);
+/// let mut component = Vec::new();
+/// while let Some(n) = dfs.next(undirected) {
+///     component.push(n);
+/// }
+///
+/// assert_eq!(component, vec![1, 2, 3]);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct AsUndirected<G>(pub G);
+
+impl<G> GraphBase for AsUndirected<G>
+where
+    G: GraphBase,
+{
+    type NodeId = G::NodeId;
+    type EdgeId = G::EdgeId;
+}
+
+impl<G> GraphRef for AsUndirected<G> where G: GraphRef {}
+
+impl<G> Data for AsUndirected<G>
+where
+    G: Data,
+{
+    type NodeWeight = G::NodeWeight;
+    type EdgeWeight = G::EdgeWeight;
+}
+
+impl<G> NodeCount for AsUndirected<G>
+where
+    G: NodeCount,
+{
+    fn node_count(&self) -> usize {
+        self.0.node_count()
+    }
+}
+
+impl<G> Visitable for AsUndirected<G>
+where
+    G: Visitable,
+{
+    type Map = G::Map;
+
+    fn visit_map(&self) -> Self::Map {
+        self.0.visit_map()
+    }
+
+    fn reset_map(&self, map: &mut Self::Map) {
+        self.0.reset_map(map)
+    }
+}
+
+impl<G> IntoNodeIdentifiers for AsUndirected<G>
+where
+    G: IntoNodeIdentifiers,
+{
+    type NodeIdentifiers = G::NodeIdentifiers;
+
+    fn node_identifiers(self) -> Self::NodeIdentifiers {
+        self.0.node_identifiers()
+    }
+}
+
+impl<'a, N, E, S> IntoNeighbors for AsUndirected<&'a Graph<N, E, Directed, S>>
+where
+    N: NodeTrait,
+    S: BuildHasher,
+{
+    type Neighbors = UndirectedNeighbors<'a, N, E, S>;
+
+    fn neighbors(self, a: N) -> Self::Neighbors {
+        UndirectedNeighbors {
+            from: a,
+            outgoing: self.0.adjacency(a, Outgoing).iter(),
+            incoming: self.0.adjacency(a, Incoming).iter(),
+            graph: self.0,
+        }
+    }
+}
+
+/// All edges are undirected, `dir` is ignored.
+impl<'a, N, E, S> IntoNeighborsDirected for AsUndirected<&'a Graph<N, E, Directed, S>>
+where
+    N: NodeTrait,
+    S: BuildHasher,
+{
+    type NeighborsDirected = UndirectedNeighbors<'a, N, E, S>;
+
+    fn neighbors_directed(self, a: N, _dir: Direction) -> Self::NeighborsDirected {
+        self.neighbors(a)
+    }
+}
+
+impl<'a, N, E, S> IntoEdgeReferences for AsUndirected<&'a Graph<N, E, Directed, S>>
+where
+    N: NodeTrait,
+    S: BuildHasher,
+    E: 'a,
+{
+    type EdgeRef = (N, N, &'a E);
+    type EdgeReferences = UndirectedEdgeReferences<'a, N, E, S>;
+
+    fn edge_references(self) -> Self::EdgeReferences {
+        UndirectedEdgeReferences {
+            iter: self.0.all_edges().enumerate(),
+            graph: self.0,
+        }
+    }
+}
+
+impl<'a, N, E, S> IntoEdges for AsUndirected<&'a Graph<N, E, Directed, S>>
+where
+    N: NodeTrait,
+    S: BuildHasher,
+    E: 'a,
+{
+    type Edges = UndirectedEdges<'a, N, E, S>;
+
+    fn edges(self, a: N) -> Self::Edges {
+        UndirectedEdges {
+            neighbors: self.neighbors(a),
+        }
+    }
+}
+
+/// Iterator of the neighbors of a node in an `AsUndirected` view, the outgoing ones first.
+///
+/// Iterator element type is `N`.
+pub struct UndirectedNeighbors<'a, N, E, S = RandomState> {
+    from: N,
+    outgoing: Iter<'a, N>,
+    incoming: Iter<'a, N>,
+    graph: &'a Graph<N, E, Directed, S>,
+}
+
+impl<'a, N, E, S> Iterator for UndirectedNeighbors<'a, N, E, S>
+where
+    N: NodeTrait,
+    S: BuildHasher,
+{
+    type Item = N;
+
+    fn next(&mut self) -> Option<N> {
+        if let Some(&n) = self.outgoing.next() {
+            return Some(n);
+        }
+
+        // Reciprocal neighbors and self loops are listed both as `Outgoing` and `Incoming`,
+        // keep the former.
+        let (from, graph) = (self.from, self.graph);
+        self.incoming
+            .find(|&&n| !graph.contains_edge(from, n))
+            .cloned()
+    }
+}
+
+/// Iterator of the edges of a node in an `AsUndirected` view.
+///
+/// Iterator element type is `(N, N, &E)`, the node being the source of every edge.
+pub struct UndirectedEdges<'a, N, E, S = RandomState> {
+    neighbors: UndirectedNeighbors<'a, N, E, S>,
+}
+
+impl<'a, N, E, S> Iterator for UndirectedEdges<'a, N, E, S>
+where
+    N: NodeTrait,
+    S: BuildHasher,
+{
+    type Item = (N, N, &'a E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let b = self.neighbors.next()?;
+        let a = self.neighbors.from;
+        let graph = self.neighbors.graph;
+
+        let key = match (graph.edge_index(a, b), graph.edge_index(b, a)) {
+            (Some(i), Some(j)) if j < i => (b, a),
+            (Some(_), _) => (a, b),
+            _ => (b, a),
+        };
+
+        graph.edge_weight(key.0, key.1).map(|weight| (a, b, weight))
+    }
+}
+
+/// Iterator of all edges of an `AsUndirected` view, in edge insertion order.
+///
+/// Iterator element type is `(N, N, &E)`.
+pub struct UndirectedEdgeReferences<'a, N, E, S = RandomState> {
+    iter: Enumerate<AllEdges<'a, N, E, Directed>>,
+    graph: &'a Graph<N, E, Directed, S>,
+}
+
+impl<'a, N, E, S> Iterator for UndirectedEdgeReferences<'a, N, E, S>
+where
+    N: NodeTrait,
+    S: BuildHasher,
+{
+    type Item = (N, N, &'a E);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let graph = self.graph;
+
+        // Skip the second edge of a reciprocal pair.
+        self.iter
+            .find(|&(i, (a, b, _))| match graph.edge_index(b, a) {
+                Some(j) => a == b || j > i,
+                None => true,
+            })
+            .map(|(_, edge)| edge)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::edge::Direction::Incoming;
+    use crate::graph::Graph;
+    use crate::visit::{
+        AsUndirected, IntoEdgeReferences, IntoEdges, IntoNeighbors, IntoNeighborsDirected,
+    };
+
+    #[test]
+    fn neighbors() {
+        let graph = Graph::<u32, ()>::from_edges(&[(1, 2), (3, 1), (2, 1), (1, 1)]);
+        let undirected = AsUndirected(&graph);
+
+        assert_eq!(undirected.neighbors(1).collect::<Vec<_>>(), vec![2, 1, 3]);
+        assert_eq!(undirected.neighbors(2).collect::<Vec<_>>(), vec![1]);
+        assert_eq!(
+            undirected
+                .neighbors_directed(3, Incoming)
+                .collect::<Vec<_>>(),
+            vec![1]
+        );
+        assert_eq!(undirected.neighbors(4).next(), None);
+    }
+
+    #[test]
+    fn edges() {
+        let graph = Graph::<u32, u32>::from_edges(&[(1, 2, 10), (3, 1, 20), (2, 1, 30)]);
+        let undirected = AsUndirected(&graph);
+
+        // Test the weight of the edge with the lower index of a reciprocal pair is used.
+        assert_eq!(
+            undirected.edges(1).collect::<Vec<_>>(),
+            vec![(1, 2, &10), (1, 3, &20)]
+        );
+        assert_eq!(undirected.edges(2).collect::<Vec<_>>(), vec![(2, 1, &10)]);
+        assert_eq!(
+            undirected.edge_references().collect::<Vec<_>>(),
+            vec![(1, 2, &10), (3, 1, &20)]
+        );
+    }
+}